@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+
+use crate::{
+    game::actions::{Action, BindingPreset, InputBindings, MenuInput, MenuKey, PadBindings},
+    menu::{cycle, text_bundle, SELECTED_COLOR},
+    misc::states::AppState,
+};
+
+const BACKGROUND_COLOR: Color = Color::rgb(0.0, 0.0, 0.0);
+const FONT_SIZE: f32 = 14.0;
+const CONFLICT_COLOR: Color = Color::rgb(1.0, 0.2, 0.2);
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ControlsCursor>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::Controls)
+                    .with_system(setup_controls)
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Controls)
+                    .with_system(controls_input.label("controls_input"))
+                    .with_system(update_controls.after("controls_input"))
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Controls)
+                    .with_system(despawn_controls)
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct ControlsScreen;
+
+/// A line of the controls screen: the preset picker or one action.
+#[derive(Component, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ControlsRow {
+    Preset,
+    Binding(Action),
+}
+
+impl ControlsRow {
+    fn all() -> Vec<ControlsRow> {
+        let mut rows = vec![ControlsRow::Preset];
+        rows.extend(Action::ALL.into_iter().map(ControlsRow::Binding));
+        rows
+    }
+}
+
+/// Highlighted row, and whether the next key pressed rebinds it.
+#[derive(Default)]
+pub struct ControlsCursor {
+    row: usize,
+    waiting: bool,
+}
+
+fn setup_controls(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut buttons: ResMut<Input<GamepadButton>>,
+    mut cursor: ResMut<ControlsCursor>,
+) {
+    // The key or button that opened this screen must not start a rebind.
+    keys.clear();
+    buttons.clear();
+    *cursor = ControlsCursor::default();
+
+    commands
+        .spawn_bundle(UiCameraBundle::default())
+        .insert(ControlsScreen);
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: BACKGROUND_COLOR.into(),
+            ..Default::default()
+        })
+        .insert(ControlsScreen)
+        .with_children(|parent| {
+            let mut title = text_bundle(&asset_server, "CONTROLS", 25.0);
+            title.style.margin = Rect::all(Val::Px(20.0));
+            parent.spawn_bundle(title);
+            for row in ControlsRow::all() {
+                parent
+                    .spawn_bundle(text_bundle(&asset_server, "", FONT_SIZE))
+                    .insert(row);
+            }
+            let mut help = text_bundle(&asset_server, "ENTER/SOUTH REBIND  BACKSPACE/EAST BACK", FONT_SIZE);
+            help.style.margin = Rect::all(Val::Px(20.0));
+            parent.spawn_bundle(help);
+        });
+}
+
+fn controls_input(
+    mut app_state: ResMut<State<AppState>>,
+    keys: Res<Input<KeyCode>>,
+    menu_input: MenuInput,
+    mut cursor: ResMut<ControlsCursor>,
+    mut bindings: ResMut<InputBindings>,
+    buttons: Res<Input<GamepadButton>>,
+    mut pad_bindings: ResMut<PadBindings>,
+) {
+    let rows = ControlsRow::all();
+    if cursor.waiting {
+        // A gamepad button rebinds the action on the gamepad it came from.
+        if let Some(&key) = keys.get_just_pressed().next() {
+            if let ControlsRow::Binding(action) = rows[cursor.row] {
+                bindings.rebind(action, key);
+            }
+            cursor.waiting = false;
+        } else if let Some(&GamepadButton(gamepad, button)) = buttons.get_just_pressed().next() {
+            if let ControlsRow::Binding(action) = rows[cursor.row] {
+                pad_bindings.0.entry(gamepad).or_default().rebind(action, button);
+            }
+            cursor.waiting = false;
+        }
+        return;
+    }
+
+    if menu_input.just_pressed(MenuKey::Back) {
+        // Opened from the pause menu, the game is still on the stack.
+        if app_state.inactives().is_empty() {
+            app_state.set(AppState::Menu).unwrap_or_default();
+        } else {
+            app_state.pop().unwrap_or_default();
+        }
+    } else if menu_input.just_pressed(MenuKey::Down) {
+        cursor.row = (cursor.row + 1) % rows.len();
+    } else if menu_input.just_pressed(MenuKey::Up) {
+        cursor.row = (cursor.row + rows.len() - 1) % rows.len();
+    } else if rows[cursor.row] == ControlsRow::Preset {
+        let step = if menu_input.just_pressed(MenuKey::Right) {
+            1
+        } else if menu_input.just_pressed(MenuKey::Left) {
+            -1
+        } else {
+            return;
+        };
+        let current = bindings.preset().unwrap_or(BindingPreset::Wasd);
+        *bindings = InputBindings::from_preset(cycle(&BindingPreset::ALL, current, step));
+    } else if menu_input.just_pressed(MenuKey::Confirm) {
+        cursor.waiting = true;
+    }
+}
+
+fn update_controls(
+    cursor: Res<ControlsCursor>,
+    bindings: Res<InputBindings>,
+    gamepads: Res<Gamepads>,
+    pad_bindings: Res<PadBindings>,
+    mut row_query: Query<(&ControlsRow, &mut Text)>,
+) {
+    let rows = ControlsRow::all();
+    // The buttons of the first connected gamepad are listed after the keys.
+    let pad = gamepads.iter().next().and_then(|gamepad| pad_bindings.0.get(gamepad));
+    for (row, mut text) in row_query.iter_mut() {
+        let selected = rows[cursor.row] == *row;
+        let (label, conflict) = match row {
+            ControlsRow::Preset => {
+                let name = bindings.preset().map_or("CUSTOM", |preset| preset.name());
+                (format!("PRESET {}", name), false)
+            }
+            ControlsRow::Binding(action) => {
+                let keys = if selected && cursor.waiting {
+                    String::from("PRESS A KEY OR BUTTON")
+                } else {
+                    let keys = names(bindings.keys(*action));
+                    match pad {
+                        Some(pad) => format!("{} / {}", keys, names(pad.buttons(*action))),
+                        None => keys,
+                    }
+                };
+                (format!("{} {}", action.name(), keys), !bindings.conflicts(*action).is_empty())
+            }
+        };
+
+        text.sections[0].value = if selected {
+            format!("< {} >", label)
+        } else {
+            label
+        };
+        text.sections[0].style.color = if conflict {
+            CONFLICT_COLOR
+        } else if selected {
+            SELECTED_COLOR
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+/// Upper case names of keys or buttons, as the controls screen lists them.
+fn names<T: std::fmt::Debug>(inputs: &[T]) -> String {
+    inputs
+        .iter()
+        .map(|input| format!("{:?}", input).to_uppercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn despawn_controls(
+    mut commands: Commands,
+    controls_query: Query<Entity, With<ControlsScreen>>,
+) {
+    for entity in controls_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,471 @@
+use bevy::{ecs::system::SystemParam, input::InputSystem, prelude::*};
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use tetris_core::directions::Direction;
+
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ActionSet>()
+            .init_resource::<InputBindings>()
+            .init_resource::<PadBindings>()
+            .add_system_to_stage(CoreStage::PreUpdate, gamepad_connections.label("gamepad_connections").after(InputSystem))
+            .add_system_to_stage(CoreStage::PreUpdate, read_actions.after("gamepad_connections"));
+    }
+}
+
+/// Everything a player can ask of the game in one frame.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    HardDrop,
+    RotateClockwise,
+    RotateCounterClockwise,
+    Rotate180,
+    Hold,
+    Pause,
+    Undo,
+    GravityUp,
+    GravityDown,
+}
+
+impl Action {
+    pub const ALL: [Action; 12] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::SoftDrop,
+        Action::HardDrop,
+        Action::RotateClockwise,
+        Action::RotateCounterClockwise,
+        Action::Rotate180,
+        Action::Hold,
+        Action::Pause,
+        Action::Undo,
+        Action::GravityUp,
+        Action::GravityDown,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "MOVE LEFT",
+            Action::MoveRight => "MOVE RIGHT",
+            Action::SoftDrop => "SOFT DROP",
+            Action::HardDrop => "HARD DROP",
+            Action::RotateClockwise => "ROTATE CW",
+            Action::RotateCounterClockwise => "ROTATE CCW",
+            Action::Rotate180 => "ROTATE 180",
+            Action::Hold => "HOLD",
+            Action::Pause => "PAUSE",
+            Action::Undo => "UNDO",
+            Action::GravityUp => "GRAVITY UP",
+            Action::GravityDown => "GRAVITY DOWN",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BindingPreset {
+    Wasd,
+    Arrows,
+    Guideline,
+}
+
+impl BindingPreset {
+    pub const ALL: [BindingPreset; 3] = [BindingPreset::Wasd, BindingPreset::Arrows, BindingPreset::Guideline];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BindingPreset::Wasd => "WASD",
+            BindingPreset::Arrows => "ARROWS",
+            BindingPreset::Guideline => "GUIDELINE",
+        }
+    }
+
+    fn keys(&self, action: Action) -> Vec<KeyCode> {
+        let keys: &[KeyCode] = match (self, action) {
+            (BindingPreset::Wasd, Action::MoveLeft) => &[KeyCode::A],
+            (BindingPreset::Wasd, Action::MoveRight) => &[KeyCode::D],
+            (BindingPreset::Wasd, Action::SoftDrop) => &[KeyCode::S],
+            (BindingPreset::Wasd, Action::HardDrop) => &[KeyCode::Space],
+            (BindingPreset::Wasd, Action::RotateClockwise) => &[KeyCode::E],
+            (BindingPreset::Wasd, Action::RotateCounterClockwise) => &[KeyCode::Q],
+            (BindingPreset::Wasd, Action::Rotate180) => &[KeyCode::W],
+            (BindingPreset::Wasd, Action::Hold) => &[KeyCode::C, KeyCode::LShift],
+            (BindingPreset::Arrows, Action::MoveLeft) => &[KeyCode::Left],
+            (BindingPreset::Arrows, Action::MoveRight) => &[KeyCode::Right],
+            (BindingPreset::Arrows, Action::SoftDrop) => &[KeyCode::Down],
+            (BindingPreset::Arrows, Action::HardDrop) => &[KeyCode::Up],
+            (BindingPreset::Arrows, Action::RotateClockwise) => &[KeyCode::Period],
+            (BindingPreset::Arrows, Action::RotateCounterClockwise) => &[KeyCode::Comma],
+            (BindingPreset::Arrows, Action::Rotate180) => &[KeyCode::Slash],
+            (BindingPreset::Arrows, Action::Hold) => &[KeyCode::RShift],
+            (BindingPreset::Guideline, Action::MoveLeft) => &[KeyCode::Left],
+            (BindingPreset::Guideline, Action::MoveRight) => &[KeyCode::Right],
+            (BindingPreset::Guideline, Action::SoftDrop) => &[KeyCode::Down],
+            (BindingPreset::Guideline, Action::HardDrop) => &[KeyCode::Space],
+            (BindingPreset::Guideline, Action::RotateClockwise) => &[KeyCode::X, KeyCode::Up],
+            (BindingPreset::Guideline, Action::RotateCounterClockwise) => &[KeyCode::Z, KeyCode::LControl],
+            (BindingPreset::Guideline, Action::Rotate180) => &[KeyCode::A],
+            (BindingPreset::Guideline, Action::Hold) => &[KeyCode::C, KeyCode::LShift],
+            (_, Action::Pause) => &[KeyCode::P, KeyCode::F1],
+            (_, Action::Undo) => &[KeyCode::Back],
+            (_, Action::GravityUp) => &[KeyCode::Equals, KeyCode::NumpadAdd],
+            (_, Action::GravityDown) => &[KeyCode::Minus, KeyCode::NumpadSubtract],
+        };
+        keys.to_vec()
+    }
+}
+
+/// Keys bound to each action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputBindings(pub HashMap<Action, Vec<KeyCode>>);
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        InputBindings::from_preset(BindingPreset::Wasd)
+    }
+}
+
+impl InputBindings {
+    pub fn from_preset(preset: BindingPreset) -> Self {
+        InputBindings(Action::ALL.into_iter().map(|action| (action, preset.keys(action))).collect())
+    }
+
+    /// The preset these bindings match exactly, if any.
+    pub fn preset(&self) -> Option<BindingPreset> {
+        BindingPreset::ALL.into_iter().find(|&preset| *self == InputBindings::from_preset(preset))
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.0.get(&action).map_or(&[], |keys| keys.as_slice())
+    }
+
+    /// Replaces the keys of `action` with just `key`.
+    pub fn rebind(&mut self, action: Action, key: KeyCode) {
+        self.0.insert(action, vec![key]);
+    }
+
+    /// Actions sharing a key with `action`.
+    pub fn conflicts(&self, action: Action) -> Vec<Action> {
+        Action::ALL
+            .into_iter()
+            .filter(|&other| other != action)
+            .filter(|&other| self.keys(other).iter().any(|key| self.keys(action).contains(key)))
+            .collect()
+    }
+}
+
+/// How far the left stick must lean before it counts as a held direction.
+pub const STICK_THRESHOLD: f32 = 0.5;
+
+/// Gamepad buttons bound to each action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GamepadBindings(pub HashMap<Action, Vec<GamepadButtonType>>);
+
+impl Default for GamepadBindings {
+    fn default() -> Self {
+        GamepadBindings(Action::ALL.into_iter().map(|action| {
+            let buttons: &[GamepadButtonType] = match action {
+                Action::MoveLeft => &[GamepadButtonType::DPadLeft],
+                Action::MoveRight => &[GamepadButtonType::DPadRight],
+                Action::SoftDrop => &[GamepadButtonType::DPadDown],
+                Action::HardDrop => &[GamepadButtonType::DPadUp, GamepadButtonType::North],
+                Action::RotateClockwise => &[GamepadButtonType::South],
+                Action::RotateCounterClockwise => &[GamepadButtonType::East],
+                Action::Rotate180 => &[GamepadButtonType::RightTrigger],
+                Action::Hold => &[GamepadButtonType::West, GamepadButtonType::LeftTrigger],
+                Action::Pause => &[GamepadButtonType::Start],
+                Action::Undo => &[GamepadButtonType::Select],
+                Action::GravityUp => &[GamepadButtonType::RightTrigger2],
+                Action::GravityDown => &[GamepadButtonType::LeftTrigger2],
+            };
+            (action, buttons.to_vec())
+        }).collect())
+    }
+}
+
+impl GamepadBindings {
+    pub fn buttons(&self, action: Action) -> &[GamepadButtonType] {
+        self.0.get(&action).map_or(&[], |buttons| buttons.as_slice())
+    }
+
+    /// Replaces the buttons of `action` with just `button`.
+    pub fn rebind(&mut self, action: Action, button: GamepadButtonType) {
+        self.0.insert(action, vec![button]);
+    }
+}
+
+/// Bindings of every gamepad seen so far. They are kept when a gamepad is
+/// unplugged so it gets them back when it reconnects.
+#[derive(Debug, Clone, Default)]
+pub struct PadBindings(pub HashMap<Gamepad, GamepadBindings>);
+
+/// Actions held and newly pressed this frame.
+#[derive(Debug, Clone, Default)]
+pub struct ActionSet {
+    held: HashSet<Action>,
+    pressed: HashSet<Action>,
+    /// Presses no game tick has seen yet. A frame can run no tick or
+    /// several, so each press is kept until exactly one tick acts on it.
+    queued: HashSet<Action>,
+    horizontal: Option<Direction>,
+}
+
+impl ActionSet {
+    pub fn held(&self, action: Action) -> bool {
+        self.held.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    /// Pressed since the last game tick.
+    pub fn queued(&self, action: Action) -> bool {
+        self.queued.contains(&action)
+    }
+
+    pub fn end_tick(&mut self) {
+        self.queued.clear();
+    }
+
+    /// Held horizontal direction. When both are held the one pressed last
+    /// wins, and releasing it falls back to the other.
+    pub fn horizontal(&self) -> Option<Direction> {
+        self.horizontal
+    }
+
+    /// Forgets this frame's presses, so the state a press switched to does
+    /// not act on it as well.
+    pub fn clear_pressed(&mut self) {
+        self.pressed.clear();
+        self.queued.clear();
+    }
+
+    pub fn update(&mut self, held: HashSet<Action>, pressed: HashSet<Action>) {
+        let left = held.contains(&Action::MoveLeft);
+        let right = held.contains(&Action::MoveRight);
+        let pressed_left = pressed.contains(&Action::MoveLeft);
+        let pressed_right = pressed.contains(&Action::MoveRight);
+
+        self.horizontal = match self.horizontal {
+            _ if pressed_left && !pressed_right => Some(Direction::Left),
+            _ if pressed_right && !pressed_left => Some(Direction::Right),
+            Some(Direction::Left) if left => Some(Direction::Left),
+            Some(Direction::Right) if right => Some(Direction::Right),
+            _ if left => Some(Direction::Left),
+            _ if right => Some(Direction::Right),
+            _ => None,
+        };
+        self.held = held;
+        self.queued.extend(pressed.iter().copied());
+        self.pressed = pressed;
+    }
+}
+
+/// Keys that move around the menus and overlays.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MenuKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+
+impl MenuKey {
+    fn key(&self) -> KeyCode {
+        match self {
+            MenuKey::Up => KeyCode::Up,
+            MenuKey::Down => KeyCode::Down,
+            MenuKey::Left => KeyCode::Left,
+            MenuKey::Right => KeyCode::Right,
+            MenuKey::Confirm => KeyCode::Return,
+            MenuKey::Back => KeyCode::Back,
+        }
+    }
+
+    fn buttons(&self) -> &'static [GamepadButtonType] {
+        match self {
+            MenuKey::Up => &[GamepadButtonType::DPadUp],
+            MenuKey::Down => &[GamepadButtonType::DPadDown],
+            MenuKey::Left => &[GamepadButtonType::DPadLeft],
+            MenuKey::Right => &[GamepadButtonType::DPadRight],
+            MenuKey::Confirm => &[GamepadButtonType::South, GamepadButtonType::Start],
+            MenuKey::Back => &[GamepadButtonType::East, GamepadButtonType::Select],
+        }
+    }
+}
+
+/// Menu navigation from the keyboard or any connected gamepad. Menus aren't
+/// rebindable, so this reads the fixed `MenuKey` keys and buttons directly.
+#[derive(SystemParam)]
+pub struct MenuInput<'w, 's> {
+    keys: Res<'w, Input<KeyCode>>,
+    gamepads: Res<'w, Gamepads>,
+    buttons: Res<'w, Input<GamepadButton>>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's> MenuInput<'w, 's> {
+    pub fn just_pressed(&self, menu_key: MenuKey) -> bool {
+        self.keys.just_pressed(menu_key.key())
+            || self.gamepads.iter().any(|&gamepad| {
+                let pad_buttons = menu_key.buttons().iter().map(|&button| GamepadButton(gamepad, button));
+                self.buttons.any_just_pressed(pad_buttons)
+            })
+    }
+}
+
+fn gamepad_connections(
+    mut gamepad_events: EventReader<GamepadEvent>,
+    mut pad_bindings: ResMut<PadBindings>,
+) {
+    for GamepadEvent(gamepad, event) in gamepad_events.iter() {
+        if *event == GamepadEventType::Connected {
+            pad_bindings.0.entry(*gamepad).or_default();
+        }
+    }
+}
+
+/// Actions the left stick of `gamepad` leans towards.
+fn stick_actions(axes: &Axis<GamepadAxis>, gamepad: Gamepad) -> Vec<Action> {
+    let x = axes.get(GamepadAxis(gamepad, GamepadAxisType::LeftStickX)).unwrap_or(0.0);
+    let y = axes.get(GamepadAxis(gamepad, GamepadAxisType::LeftStickY)).unwrap_or(0.0);
+    let mut actions = Vec::new();
+    if x <= -STICK_THRESHOLD {
+        actions.push(Action::MoveLeft);
+    } else if x >= STICK_THRESHOLD {
+        actions.push(Action::MoveRight);
+    }
+    if y <= -STICK_THRESHOLD {
+        actions.push(Action::SoftDrop);
+    }
+    actions
+}
+
+fn read_actions(
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<InputBindings>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    pad_bindings: Res<PadBindings>,
+    mut stick_held: Local<HashSet<Action>>,
+    mut actions: ResMut<ActionSet>,
+) {
+    let mut held: HashSet<Action> = Action::ALL
+        .into_iter()
+        .filter(|&action| keys.any_pressed(bindings.keys(action).iter().copied()))
+        .collect();
+    let mut pressed: HashSet<Action> = Action::ALL
+        .into_iter()
+        .filter(|&action| keys.any_just_pressed(bindings.keys(action).iter().copied()))
+        .collect();
+
+    let mut stick = HashSet::new();
+    for &gamepad in gamepads.iter() {
+        if let Some(pad) = pad_bindings.0.get(&gamepad) {
+            for action in Action::ALL {
+                let pad_buttons = pad.buttons(action).iter().map(|&button| GamepadButton(gamepad, button));
+                if buttons.any_pressed(pad_buttons.clone()) {
+                    held.insert(action);
+                }
+                if buttons.any_just_pressed(pad_buttons) {
+                    pressed.insert(action);
+                }
+            }
+        }
+        stick.extend(stick_actions(&axes, gamepad));
+    }
+    // The stick has no press events of its own, so it presses an action on
+    // the frame it crosses the threshold.
+    pressed.extend(stick.difference(&stick_held).copied());
+    held.extend(stick.iter().copied());
+    *stick_held = stick;
+
+    actions.update(held, pressed);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        input::gamepad::{GamepadAxisType, GamepadEventType},
+        prelude::*,
+    };
+
+    use super::*;
+    use crate::game::testing::{app, game, pad};
+
+    const GAMEPAD: Gamepad = Gamepad(0);
+
+    fn button(app: &mut App, button: GamepadButtonType, value: f32) {
+        pad(app, GAMEPAD, GamepadEventType::ButtonChanged(button, value));
+    }
+
+    #[test]
+    fn dpad_press_shifts_the_piece() {
+        let mut app = app(3);
+        pad(&mut app, GAMEPAD, GamepadEventType::Connected);
+        app.update();
+        let spawned = game(&app).active().unwrap();
+
+        button(&mut app, GamepadButtonType::DPadRight, 1.0);
+        app.update();
+        assert_eq!(game(&app).active().unwrap(), spawned.shifted(1, 0));
+    }
+
+    #[test]
+    fn stick_past_the_threshold_is_one_press() {
+        let mut app = app(3);
+        pad(&mut app, GAMEPAD, GamepadEventType::Connected);
+        app.update();
+        let spawned = game(&app).active().unwrap();
+
+        let lean = -(STICK_THRESHOLD + 0.1);
+        pad(&mut app, GAMEPAD, GamepadEventType::AxisChanged(GamepadAxisType::LeftStickX, lean));
+        app.update();
+        let actions = app.world.get_resource::<ActionSet>().unwrap();
+        assert!(actions.just_pressed(Action::MoveLeft));
+        assert_eq!(game(&app).active().unwrap(), spawned.shifted(-1, 0));
+
+        // Still leaning, but short of the auto shift delay.
+        for _ in 0..5 {
+            app.update();
+            let actions = app.world.get_resource::<ActionSet>().unwrap();
+            assert!(actions.held(Action::MoveLeft) && !actions.just_pressed(Action::MoveLeft));
+        }
+        assert_eq!(game(&app).active().unwrap().origin.0, spawned.origin.0 - 1);
+    }
+
+    #[test]
+    fn bindings_survive_a_reconnect() {
+        let mut app = app(3);
+        pad(&mut app, GAMEPAD, GamepadEventType::Connected);
+        app.update();
+        app.world
+            .get_resource_mut::<PadBindings>()
+            .unwrap()
+            .0
+            .get_mut(&GAMEPAD)
+            .unwrap()
+            .rebind(Action::HardDrop, GamepadButtonType::Mode);
+
+        pad(&mut app, GAMEPAD, GamepadEventType::Disconnected);
+        app.update();
+        pad(&mut app, GAMEPAD, GamepadEventType::Connected);
+        app.update();
+        button(&mut app, GamepadButtonType::Mode, 1.0);
+        app.update();
+        assert_eq!(game(&app).board().occupied().count(), 4);
+    }
+}
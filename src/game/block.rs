@@ -1,4 +1,102 @@
 use bevy::prelude::*;
 
+use tetris_core::{board::{Board, BOARD_WIDTH, VISIBLE_HEIGHT}, shapes::Shape, Game};
+
+pub const TILE_SIZE: f32 = 24.0;
+const TILE_SPRITE: &str = "textures/TetrisTiles.png";
+
+pub struct BlockPlugin;
+
+impl Plugin for BlockPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<TileAtlas>()
+            .add_system(sync_block_sprites);
+    }
+}
+
 #[derive(Component)]
-pub struct Block;
\ No newline at end of file
+pub struct Block;
+
+/// Texture atlas shared by every tile sprite.
+pub struct TileAtlas(pub Handle<TextureAtlas>);
+
+impl FromWorld for TileAtlas {
+    fn from_world(world: &mut World) -> Self {
+        let texture = world.get_resource::<AssetServer>().unwrap().load(TILE_SPRITE);
+        let texture_atlas = TextureAtlas::from_grid(texture, Vec2::new(TILE_SIZE, TILE_SIZE), 5, 7);
+        let mut texture_atlases = world.get_resource_mut::<Assets<TextureAtlas>>().unwrap();
+        TileAtlas(texture_atlases.add(texture_atlas))
+    }
+}
+
+/// World position of the center of a board cell.
+pub fn cell_translation((x, y): (i32, i32)) -> Vec3 {
+    Vec3::new(
+        (x as f32 - BOARD_WIDTH as f32 / 2.0) * TILE_SIZE + TILE_SIZE / 2.0,
+        (y as f32 - VISIBLE_HEIGHT as f32 / 2.0) * TILE_SIZE + TILE_SIZE / 2.0,
+        0.0,
+    )
+}
+
+pub fn tile_bundle(tile_atlas: &TileAtlas, tile: usize, cell: (i32, i32)) -> SpriteSheetBundle {
+    SpriteSheetBundle {
+        texture_atlas: tile_atlas.0.clone(),
+        transform: Transform::from_translation(cell_translation(cell)),
+        sprite: TextureAtlasSprite::new(tile),
+        visibility: Visibility { is_visible: cell.1 < VISIBLE_HEIGHT },
+        ..Default::default()
+    }
+}
+
+/// Tile sprite drawn outside the board, such as in the next-piece preview.
+pub fn panel_tile_bundle(tile_atlas: &TileAtlas, tile: usize, translation: Vec3, scale: f32) -> SpriteSheetBundle {
+    SpriteSheetBundle {
+        texture_atlas: tile_atlas.0.clone(),
+        transform: Transform {
+            translation,
+            scale: Vec3::new(scale, scale, 1.0),
+            ..Default::default()
+        },
+        sprite: TextureAtlasSprite::new(tile),
+        ..Default::default()
+    }
+}
+
+/// Centers of the four tiles of `shape` in spawn orientation, laid out
+/// around `center` with tiles `size` pixels wide.
+pub fn shape_translations(shape: Shape, center: Vec2, size: f32) -> [Vec3; 4] {
+    let minos = shape.minos();
+    let (min_x, max_x) = (minos.iter().map(|m| m.0).min().unwrap(), minos.iter().map(|m| m.0).max().unwrap());
+    let (min_y, max_y) = (minos.iter().map(|m| m.1).min().unwrap(), minos.iter().map(|m| m.1).max().unwrap());
+    let offset = Vec2::new((min_x + max_x) as f32, (min_y + max_y) as f32) / 2.0;
+    minos.map(|(x, y)| {
+        let position = center + (Vec2::new(x as f32, y as f32) - offset) * size;
+        position.extend(0.0)
+    })
+}
+
+/// Rebuilds the locked tile sprites whenever the board changes, and clears
+/// them once the game is gone.
+fn sync_block_sprites(
+    mut commands: Commands,
+    game: Option<Res<Game>>,
+    tile_atlas: Res<TileAtlas>,
+    mut drawn: Local<Board>,
+    block_query: Query<Entity, With<Block>>,
+) {
+    let board = game.map_or_else(Board::default, |game| game.board().clone());
+    if board == *drawn {
+        return;
+    }
+
+    for entity in block_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for (cell, tile) in board.occupied() {
+        commands
+            .spawn_bundle(tile_bundle(&tile_atlas, tile, cell))
+            .insert(Block);
+    }
+    *drawn = board;
+}
\ No newline at end of file
@@ -1,88 +1,114 @@
 use bevy::prelude::*;
-use crate::{
-    game::{
-        player::{Player, Block},
-    },
-    game::player::{VerticalDirection, HorizontalDirection},
-    misc::{directions::Direction, states::AppState},
-};
 
-pub struct CollisionPlugin;
+use tetris_core::{Game, GameEvent};
+
+use crate::misc::states::AppState;
+
+use super::{movement::TickInputs, tick::{playing, GameTick}};
 
-const HORIZONTAL_TICK: f64 = 0.15;
+pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
         app
-            .init_resource::<VerticalFlag>()
-            .init_resource::<CollisionUpdate>()
-            .add_system_set(
-                SystemSet::on_update(AppState::Moving) 
+            .add_event::<GameEvent>()
+            .add_system_set_to_stage(
+                GameTick,
+                SystemSet::new()
+                    .with_run_criteria(playing)
                     .label("collision")
-                    .with_system(vertical_collision_system)
-                    .with_system(horizontal_collision_system)
+                    .with_system(step_game)
             );
     }
 }
 
-#[derive(Default)]
-pub struct VerticalFlag(pub bool);
-
-#[derive(Default)]
-pub struct CollisionUpdate(pub f64);
-
-fn vertical_collision_system(
-    mut commands: Commands,
+/// Plays one tick of the game and passes on what happened in it.
+fn step_game(
     mut app_state: ResMut<State<AppState>>,
-    mut vertical_flag: ResMut<VerticalFlag>, 
-    mut collision_update: ResMut<CollisionUpdate>,
-    time: Res<Time>,
-    mut player_query: Query<(&mut Player, &Transform)>,
-    mut entity_player_query: Query<Entity, With<Player>>,
-    block_query: Query<(&Block, &Transform)>,
-    mut vertical_direction: ResMut<VerticalDirection>,
+    game: Option<ResMut<Game>>,
+    inputs: Res<TickInputs>,
+    mut game_events: EventWriter<GameEvent>,
 ) {
-        for (_player, player_transform) in player_query.iter_mut() {
-            if  player_transform.translation.y <= -216.0 {
-                vertical_flag.0 = true;
-                break;
-            }
-            for (_block, block_transform) in block_query.iter() {
-                if player_transform.translation.x == block_transform.translation.x && player_transform.translation.y == block_transform.translation.y + 24.0 {
-                    vertical_flag.0 = true;
-                    break;
-                }
+    let mut game = match game {
+        Some(game) => game,
+        None => return,
+    };
+
+    for event in game.step(&inputs.0) {
+        match event {
+            GameEvent::GameOver(_) | GameEvent::Finished => {
+                app_state.set(AppState::GameOver).unwrap_or_default();
             }
+            _ => (),
         }
-        
-        if !vertical_flag.0 {
-            collision_update.0 = time.seconds_since_startup();
-        } else {
-            for (_player, _transform) in player_query.iter_mut() {
-                vertical_direction.0 = None;
+        game_events.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{app::Events, prelude::*};
+    use tetris_core::GameEvent;
+
+    use crate::{
+        game::testing::{app, game, press, release},
+        misc::states::AppState,
+    };
+
+    #[test]
+    fn hard_drop_key_locks_the_piece() {
+        let mut app = app(3);
+        let mut reader = app
+            .world
+            .get_resource::<Events<GameEvent>>()
+            .unwrap()
+            .get_reader();
+        press(&mut app, KeyCode::Space);
+        app.update();
+
+        let events = app.world.get_resource::<Events<GameEvent>>().unwrap();
+        assert!(matches!(reader.iter(events).next(), Some(GameEvent::Locked(_))));
+        assert_eq!(game(&app).board().occupied().count(), 4);
+    }
+
+    #[test]
+    fn held_keys_shift_to_both_walls() {
+        let mut app = app(3);
+        let cases = [(KeyCode::A, 0), (KeyCode::D, 9)];
+        for (key_code, wall) in cases {
+            press(&mut app, key_code);
+            for _ in 0..30 {
+                app.update();
             }
+            release(&mut app, key_code);
+
+            let cells = game(&app).active().unwrap().cells();
+            assert!(cells.iter().any(|cell| cell.0 == wall), "{:?}", key_code);
         }
-    if time.seconds_since_startup() - collision_update.0 > HORIZONTAL_TICK && vertical_flag.0 {
-        vertical_direction.0 = None;
-        for entity in entity_player_query.iter_mut() {
-            commands.entity(entity)
-                .remove::<Player>()
-                .insert(Block);
+    }
+
+    #[test]
+    fn rotation_key_turns_once_per_press() {
+        let mut app = app(3);
+        press(&mut app, KeyCode::E);
+        for _ in 0..3 {
+            app.update();
         }
-        app_state.set(AppState::Still).unwrap_or_default();
-        println!("{:?}", app_state);
+        assert_eq!(game(&app).active().unwrap().rotation, 1);
     }
-}
 
-fn horizontal_collision_system(
-    mut player_query: Query<(&mut Player, &mut Transform)>,
-    mut horizontal_direction: ResMut<HorizontalDirection>,
-) {
-    for (_player, transform) in player_query.iter_mut() {
-        if transform.translation.x <= -96.0  && horizontal_direction.0 == Some(Direction::Left) {
-            horizontal_direction.0 = None;
-        } else if transform.translation.x >= 86.0 && horizontal_direction.0 == Some(Direction::Right) {
-            horizontal_direction.0 = None;
+    #[test]
+    fn topping_out_ends_the_game() {
+        let mut app = app(3);
+        for _ in 0..100 {
+            press(&mut app, KeyCode::Space);
+            app.update();
+            release(&mut app, KeyCode::Space);
+            app.update();
         }
+
+        assert!(game(&app).top_out().is_some());
+        let state = app.world.get_resource::<State<AppState>>().unwrap();
+        assert_eq!(state.current(), &AppState::GameOver);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use tetris_core::{board::VISIBLE_HEIGHT, Game};
+
+use crate::misc::states::AppState;
+
+use super::{
+    block::{cell_translation, tile_bundle, TileAtlas},
+    setup_game::Playfield,
+};
+
+const GHOST_ALPHA: f32 = 0.3;
+const GHOST_Z: f32 = -0.5;
+
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_enter(AppState::Game)
+                    .with_system(setup_ghost)
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Moving)
+                    .with_system(sync_ghost_sprites)
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct Ghost;
+
+fn setup_ghost(
+    mut commands: Commands,
+    tile_atlas: Res<TileAtlas>,
+) {
+    for _ in 0..4 {
+        let mut bundle = tile_bundle(&tile_atlas, 0, (0, VISIBLE_HEIGHT));
+        bundle.sprite.color.set_a(GHOST_ALPHA);
+        commands
+            .spawn_bundle(bundle)
+            .insert(Ghost)
+            .insert(Playfield);
+    }
+}
+
+/// Projects the active piece down to its landing row.
+fn sync_ghost_sprites(
+    game: Option<Res<Game>>,
+    mut ghost_query: Query<(&mut Transform, &mut Visibility, &mut TextureAtlasSprite), With<Ghost>>,
+) {
+    let game = match game {
+        Some(game) => game,
+        None => return,
+    };
+
+    let landing = game.active().filter(|_| game.settings().ghost).map(|piece| game.board().landing(&piece));
+    for (index, (mut transform, mut visibility, mut sprite)) in ghost_query.iter_mut().enumerate() {
+        match landing {
+            Some(landing) => {
+                let cell = landing.cells()[index];
+                transform.translation = cell_translation(cell);
+                transform.translation.z = GHOST_Z;
+                visibility.is_visible = cell.1 < VISIBLE_HEIGHT;
+                sprite.index = game.tile();
+            }
+            None => visibility.is_visible = false,
+        }
+    }
+}
\ No newline at end of file
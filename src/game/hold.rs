@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+
+use tetris_core::{shapes::Shape, Game};
+
+use crate::misc::states::AppState;
+
+use super::{
+    block::{panel_tile_bundle, shape_translations, TileAtlas, TILE_SIZE},
+    setup_game::{panel_bundle, Playfield},
+};
+
+const PANEL_CENTER: [f32; 2] = [-204.0, 180.0];
+const PANEL_SIZE: [f32; 2] = [120.0, 120.0];
+const HOLD_SCALE: f32 = 0.75;
+const UNAVAILABLE_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
+
+pub struct HoldPlugin;
+
+impl Plugin for HoldPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_enter(AppState::Game)
+                    .with_system(setup_hold)
+            )
+            .add_system(sync_hold_tiles);
+    }
+}
+
+#[derive(Component)]
+pub struct HoldTile;
+
+fn setup_hold(
+    mut commands: Commands,
+) {
+    commands
+        .spawn_bundle(panel_bundle(PANEL_SIZE, PANEL_CENTER))
+        .insert(Playfield);
+}
+
+/// Redraws the hold panel, greyed out while a swap is not allowed.
+fn sync_hold_tiles(
+    mut commands: Commands,
+    game: Option<Res<Game>>,
+    tile_atlas: Res<TileAtlas>,
+    mut drawn: Local<(Option<(Shape, usize)>, bool)>,
+    hold_query: Query<Entity, With<HoldTile>>,
+) {
+    let hold = game.map_or((None, true), |game| (game.hold(), game.hold_available()));
+    if hold == *drawn {
+        return;
+    }
+
+    for entity in hold_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    if let Some((shape, tile)) = hold.0 {
+        let center = Vec2::new(PANEL_CENTER[0], PANEL_CENTER[1]);
+        for translation in shape_translations(shape, center, TILE_SIZE * HOLD_SCALE) {
+            let mut bundle = panel_tile_bundle(&tile_atlas, tile, translation, HOLD_SCALE);
+            if !hold.1 {
+                bundle.sprite.color = UNAVAILABLE_COLOR;
+            }
+            commands
+                .spawn_bundle(bundle)
+                .insert(HoldTile);
+        }
+    }
+    *drawn = hold;
+}
\ No newline at end of file
@@ -2,4 +2,12 @@ pub mod collision;
 pub mod player;
 pub mod setup_game;
 pub mod movement;
-pub mod block;
\ No newline at end of file
+pub mod block;
+pub mod score;
+pub mod preview;
+pub mod hold;
+pub mod ghost;
+pub mod actions;
+pub mod tick;
+#[cfg(test)]
+mod testing;
\ No newline at end of file
@@ -1,141 +1,66 @@
 use bevy::prelude::*;
-use rand::Rng;
+use tetris_core::{board::VISIBLE_HEIGHT, Game};
+
 use crate::misc::states::AppState;
-use crate::misc::directions::Direction;
-use crate::misc::shapes::Shape;
 
-use super::collision::{CollisionUpdate, VerticalFlag};
-use super::movement::{LastVerticalUpdate, LastHorizontalUpdate};
-use super::setup_game::NextShape;
+use super::block::{cell_translation, tile_bundle, TileAtlas};
+
+/// Tint of a piece whose lock delay has run out.
+const LOCKED_BRIGHTNESS: f32 = 0.4;
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
-            .insert_resource(VerticalDirection(Some(Direction::Down)))
-            .insert_resource(HorizontalDirection(None))
-            .init_resource::<Center>()
             .add_system_set(
-                SystemSet::on_exit(AppState::Moving)
-                    .with_system(spawn_block)
-                    .with_system(reset_player)
+                SystemSet::on_enter(AppState::Game)
+                    .with_system(setup_player)
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Moving)
+                    .with_system(sync_player_sprites)
             );
     }
 }
 
-pub struct VerticalDirection(pub Option<Direction>);
-pub struct HorizontalDirection(pub Option<Direction>);
-
-#[derive(Default)]
-pub struct Center([f32; 2]);
-
 #[derive(Component)]
 pub struct Player;
 
-#[derive(Component)]
-pub struct Block;
-
-const PLAYER_SPRITE: &str = "textures/TetrisTiles.png";
-
-pub fn spawn_block(
+fn setup_player(
     mut commands: Commands,
-    mut app_state: ResMut<State<AppState>>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    asset_server: Res<AssetServer>,
-    mut center: ResMut<Center>,
-    mut next_shape: ResMut<NextShape>,
+    tile_atlas: Res<TileAtlas>,
 ) {
-    let pos: [f32; 8];
-    let new_center: [f32; 2];
-
-    let texture_atlas = TextureAtlas::from_grid(asset_server.load(PLAYER_SPRITE), Vec2::new(24.0, 24.0), 5, 7);
-    let texture_atlas_handle = texture_atlases.add(texture_atlas);
-    match next_shape.0 {
-        Shape::IBlock => {
-            pos = [12.0, 156.0, 12.0, 180.0, 12.0, 204.0, 12.0, 228.0,];
-            new_center = [12.0, 204.0];
-        }
-        Shape::JBlock => {
-            pos = [12.0, 180.0, 36.0, 180.0, 36.0, 204.0, 36.0, 228.0,];
-            new_center = [36.0, 204.0];
-        }
-        Shape::SBlock => {
-            pos = [-36.0, 204.0, -12.0, 204.0, -12.0, 228.0, 12.0, 228.0,];
-            new_center = [-12.0, 228.0];
-        }
-        Shape::ZBlock => {
-            pos = [-36.0, 228.0, -12.0, 228.0, -12.0, 204.0, 12.0, 204.0,];
-            new_center = [-12.0, 228.0];
-        }
-        Shape::TBlock => {
-            pos = [-36.0, 204.0, -12.0, 204.0, -12.0, 228.0, 12.0, 204.0,];
-            new_center = [-12.0, 228.0];
-        }
-        Shape::OBlock => {
-            pos = [-12.0, 228.0, -12.0, 204.0, 12.0, 228.0, 12.0, 204.0,];
-            new_center = [12.0, 228.0];
-        }
-        Shape::LBlock => {
-            pos = [36.0, 180.0, 12.0, 180.0, 12.0, 204.0, 12.0, 228.0,];
-            new_center = [36.0, 204.0];
-        }
+    for _ in 0..4 {
+        commands
+            .spawn_bundle(tile_bundle(&tile_atlas, 0, (0, VISIBLE_HEIGHT)))
+            .insert(Player);
     }
-
-    let mut rng = rand::thread_rng();
-    let index: usize = rng.gen_range(0..6) * 5;
-
-    commands
-    .spawn_bundle(SpriteSheetBundle {
-        texture_atlas: texture_atlas_handle.clone(),
-        transform: Transform::from_xyz(pos[0], pos[1], 0.0),
-        sprite: TextureAtlasSprite::new(index),
-        ..Default::default()
-        })
-        .insert(Player);
-    commands
-        .spawn_bundle(SpriteSheetBundle {
-            texture_atlas: texture_atlas_handle.clone(),
-            transform: Transform::from_xyz(pos[2], pos[3], 0.0), 
-            sprite: TextureAtlasSprite::new(index),
-            ..Default::default()
-        })
-        .insert(Player);
-    commands
-        .spawn_bundle(SpriteSheetBundle {
-            texture_atlas: texture_atlas_handle.clone(),
-            transform: Transform::from_xyz(pos[4], pos[5], 0.0), 
-            sprite: TextureAtlasSprite::new(index),
-            ..Default::default()
-        }) 
-        .insert(Player);
-    commands
-        .spawn_bundle(SpriteSheetBundle {
-            texture_atlas: texture_atlas_handle.clone(),
-            transform: Transform::from_xyz(pos[6], pos[7], 0.0), 
-            sprite: TextureAtlasSprite::new(index),
-            ..Default::default()
-        })
-        .insert(Player);
-    center.0 = new_center;  
-    next_shape.0 = rand::random::<Shape>(); 
-    println!("{:?}" , app_state);
-    app_state.set(AppState::Moving).unwrap_or_default();
 }
 
-fn reset_player(
-    mut vertical_direction: ResMut<VerticalDirection>,
-    mut horizontal_direction: ResMut<HorizontalDirection>,
-    mut vertical_flag: ResMut<VerticalFlag>,
-    mut collision_update: ResMut<CollisionUpdate>,
-    mut vertical_update: ResMut<LastVerticalUpdate>,
-    mut horizontal_update: ResMut<LastHorizontalUpdate>,
-    time: Res<Time>,
+/// Moves the four `Player` sprites onto the cells of the active piece,
+/// darkening them as the lock delay runs out.
+fn sync_player_sprites(
+    game: Option<Res<Game>>,
+    mut player_query: Query<(&mut Transform, &mut Visibility, &mut TextureAtlasSprite), With<Player>>,
 ) {
-    vertical_direction.0 = Some(Direction::Down);
-    horizontal_direction.0 = None;
-    vertical_flag.0 = false;
-    collision_update.0 = time.seconds_since_startup();
-    vertical_update.0 = time.seconds_since_startup();
-    horizontal_update.0 = time.seconds_since_startup();
+    let game = match game {
+        Some(game) => game,
+        None => return,
+    };
+
+    let piece = game.active();
+    for (index, (mut transform, mut visibility, mut sprite)) in player_query.iter_mut().enumerate() {
+        match piece {
+            Some(piece) => {
+                let cell = piece.cells()[index];
+                transform.translation = cell_translation(cell);
+                visibility.is_visible = cell.1 < VISIBLE_HEIGHT;
+                sprite.index = game.tile();
+                let brightness = LOCKED_BRIGHTNESS + (1.0 - LOCKED_BRIGHTNESS) * game.lock_remaining();
+                sprite.color = Color::rgb(brightness, brightness, brightness);
+            }
+            None => visibility.is_visible = false,
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+
+use tetris_core::{settings::GameSettings, shapes::Shape, Game};
+
+use crate::misc::states::AppState;
+
+use super::{
+    block::{panel_tile_bundle, shape_translations, TileAtlas, TILE_SIZE},
+    setup_game::{panel_bundle, Playfield},
+};
+
+const PANEL_CENTER: [f32; 2] = [204.0, 0.0];
+const PANEL_SIZE: [f32; 2] = [120.0, 480.0];
+const PREVIEW_SCALE: f32 = 0.75;
+const SLOT_HEIGHT: f32 = 72.0;
+
+pub struct PreviewPlugin;
+
+impl Plugin for PreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_enter(AppState::Game)
+                    .with_system(setup_preview)
+            )
+            .add_system(sync_preview_tiles);
+    }
+}
+
+#[derive(Component)]
+pub struct PreviewTile;
+
+fn setup_preview(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+) {
+    if settings.preview == 0 {
+        return;
+    }
+
+    commands
+        .spawn_bundle(panel_bundle(PANEL_SIZE, PANEL_CENTER))
+        .insert(Playfield);
+}
+
+/// Redraws the preview panel whenever the queue moves.
+fn sync_preview_tiles(
+    mut commands: Commands,
+    game: Option<Res<Game>>,
+    tile_atlas: Res<TileAtlas>,
+    mut drawn: Local<Vec<(Shape, usize)>>,
+    preview_query: Query<Entity, With<PreviewTile>>,
+) {
+    let next: Vec<(Shape, usize)> = match &game {
+        Some(game) => game.next().take(game.settings().preview).copied().collect(),
+        None => Vec::new(),
+    };
+    if next == *drawn {
+        return;
+    }
+
+    for entity in preview_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let top = PANEL_CENTER[1] + PANEL_SIZE[1] / 2.0 - SLOT_HEIGHT / 2.0;
+    for (slot, &(shape, tile)) in next.iter().enumerate() {
+        let center = Vec2::new(PANEL_CENTER[0], top - slot as f32 * SLOT_HEIGHT);
+        for translation in shape_translations(shape, center, TILE_SIZE * PREVIEW_SCALE) {
+            commands
+                .spawn_bundle(panel_tile_bundle(&tile_atlas, tile, translation, PREVIEW_SCALE))
+                .insert(PreviewTile);
+        }
+    }
+    *drawn = next;
+}
\ No newline at end of file
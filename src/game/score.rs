@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+
+use tetris_core::{
+    game::SPLIT_LINES,
+    settings::{Mode, MARATHON_LINES},
+    Game,
+};
+
+use crate::{
+    menu::START_FONT,
+    misc::states::AppState,
+    records::{format_delta, format_time, Records},
+};
+
+use super::setup_game::Playfield;
+
+const HUD_FONT_SIZE: f32 = 16.0;
+const HUD_POSITION: [f32; 2] = [-204.0, -60.0];
+/// A full `u64` seed is too wide for the HUD column, so it is split over
+/// lines of this many digits.
+const SEED_LINE_DIGITS: usize = 7;
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_enter(AppState::Game)
+                    .with_system(setup_hud)
+            )
+            .add_system(update_hud);
+    }
+}
+
+#[derive(Component)]
+pub struct Hud;
+
+fn setup_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load(START_FONT),
+                    font_size: HUD_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+                TextAlignment {
+                    horizontal: HorizontalAlign::Center,
+                    vertical: VerticalAlign::Center,
+                },
+            ),
+            transform: Transform::from_xyz(HUD_POSITION[0], HUD_POSITION[1], 0.0),
+            ..Default::default()
+        })
+        .insert(Hud)
+        .insert(Playfield);
+}
+
+fn update_hud(
+    game: Option<Res<Game>>,
+    records: Res<Records>,
+    mut hud_query: Query<&mut Text, With<Hud>>,
+) {
+    let game = match game {
+        Some(game) => game,
+        None => return,
+    };
+
+    let score = game.score();
+    let value = match game.settings().mode {
+        Mode::Marathon { endless } => {
+            let lines = if endless {
+                score.lines.to_string()
+            } else {
+                format!("{}/{}", score.lines.min(MARATHON_LINES), MARATHON_LINES)
+            };
+            format!(
+                "SCORE\n{}\n\nLEVEL\n{}\n\nLINES\n{}",
+                score.score,
+                score.level(),
+                lines,
+            )
+        }
+        Mode::Sprint { lines } => {
+            let best = records.sprint(lines);
+            let mut value = format!(
+                "TIME\n{}\n\nLINES\n{}/{}\n",
+                format_time(game.tick()),
+                score.lines.min(lines),
+                lines,
+            );
+            for (index, &split) in game.splits().iter().enumerate() {
+                let best_split = best.and_then(|best| best.splits.get(index));
+                let shown = best_split.map_or_else(|| format_time(split), |&best| format_delta(split, best));
+                value += &format!("\n{} {}", (index as u32 + 1) * SPLIT_LINES, shown);
+            }
+            value
+        }
+        Mode::Ultra => {
+            let limit = game.settings().mode.time_limit().unwrap_or_default();
+            format!(
+                "TIME\n{}\n\nSCORE\n{}\n\nLINES\n{}\n\nBEST\n{}",
+                format_time(limit.saturating_sub(game.tick())),
+                score.score,
+                score.lines,
+                records.ultra(game.settings().seed).unwrap_or_default(),
+            )
+        }
+        Mode::Master => {
+            let level = game.section_level();
+            format!(
+                "LEVEL\n{}\n{}\n\nTIME\n{}\n\nLINES\n{}",
+                level.0,
+                level.section_goal(),
+                format_time(game.tick()),
+                score.lines,
+            )
+        }
+        Mode::Zen => format!(
+            "ZEN\n\nGRAVITY\n{}\n\nLINES\n{}\n\nUNDO\n{}",
+            game.zen_gravity(),
+            score.lines,
+            game.undo_len(),
+        ),
+    };
+    let value = format!("{}\n\nSEED\n{}", value, wrap_seed(game.seed()));
+    for mut text in hud_query.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
+
+/// Splits `seed` into lines of at most `SEED_LINE_DIGITS` digits.
+fn wrap_seed(seed: u64) -> String {
+    let digits = seed.to_string();
+    let lines: Vec<&str> = digits
+        .as_bytes()
+        .chunks(SEED_LINE_DIGITS)
+        .map(|line| std::str::from_utf8(line).unwrap())
+        .collect();
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use tetris_core::board::BOARD_WIDTH;
+
+    use super::*;
+    use crate::game::block::TILE_SIZE;
+
+    #[test]
+    fn longest_seed_stays_clear_of_the_playfield() {
+        let wrapped = wrap_seed(u64::MAX);
+        assert_eq!(wrapped, "1844674\n4073709\n551615");
+
+        // The font is monospaced with square glyphs.
+        let widest = wrapped.lines().map(str::len).max().unwrap() as f32 * HUD_FONT_SIZE;
+        let playfield_left = -(BOARD_WIDTH as f32) * TILE_SIZE / 2.0;
+        assert!(HUD_POSITION[0] + widest / 2.0 < playfield_left);
+    }
+}
@@ -1,36 +1,77 @@
 use bevy::prelude::*;
-use crate::misc::{shapes::Shape, states::AppState};
-use super::player::spawn_block;
-use rand;
+use tetris_core::{settings::GameSettings, Game};
+use crate::misc::states::AppState;
+use super::{actions::ActionSet, player::Player};
+
+/// Depth of the panel backgrounds, behind every tile.
+const PANEL_Z: f32 = -1.0;
+const PANEL_COLOR: Color = Color::rgb(0.1, 0.1, 0.1);
 
 pub struct SetupGamePlugin;
 
-pub struct NextShape(pub Shape);
+/// Marks the camera and background spawned for a game.
+#[derive(Component)]
+pub struct Playfield;
 
 impl Plugin for SetupGamePlugin {
     fn build(&self, app: &mut App) {
         app
-            .insert_resource(NextShape(rand::random::<Shape>()))
             .add_system_set(
                 SystemSet::on_enter(AppState::Game)
-                    .with_system(setup_game)
-                    .with_system(spawn_block)
+                    .with_system(teardown_game.before("setup_game"))
+                    .with_system(setup_game.label("setup_game"))
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::Menu)
+                    .with_system(teardown_game)
             );
     }
 }
 
+/// The dark background of a `size` panel centred on `center`, behind the
+/// tiles drawn on it.
+pub fn panel_bundle(size: [f32; 2], center: [f32; 2]) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            color: PANEL_COLOR,
+            custom_size: Some(Vec2::new(size[0], size[1])),
+            ..Default::default()
+        },
+        transform: Transform::from_xyz(center[0], center[1], PANEL_Z),
+        ..Default::default()
+    }
+}
+
+/// Starts a game with the menu settings, on their seed or a fresh one.
 fn setup_game(
     mut commands: Commands,
+    mut app_state: ResMut<State<AppState>>,
+    settings: Res<GameSettings>,
+    mut actions: ResMut<ActionSet>,
 ) {
-    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    // The button that started the game may be bound to a game action too.
+    actions.clear_pressed();
+    let seed = settings.seed.unwrap_or_else(rand::random);
+    println!("seed {}", seed);
+    commands.insert_resource(Game::new(settings.clone(), seed));
     commands
-        .spawn_bundle(SpriteBundle {
-            sprite: Sprite {
-                color: Color::rgb(0.1, 0.1, 0.1),
-                custom_size: Some(Vec2::new(240.0, 480.0)),
-                ..Default::default()
-            },
-            ..Default::default()
-        });
-    
+        .spawn_bundle(OrthographicCameraBundle::new_2d())
+        .insert(Playfield);
+    commands
+        .spawn_bundle(panel_bundle([240.0, 480.0], [0.0, 0.0]))
+        .insert(Playfield);
+    app_state.set(AppState::Moving).unwrap_or_default();
 }
+
+/// Tears down whatever is left of the last game, whether it ended, was
+/// restarted or was quit from the pause menu. Dropping the game also
+/// despawns every `Block` sprite.
+fn teardown_game(
+    mut commands: Commands,
+    entity_query: Query<Entity, Or<(With<Player>, With<Playfield>)>>,
+) {
+    for entity in entity_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<Game>();
+}
\ No newline at end of file
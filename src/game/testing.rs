@@ -0,0 +1,64 @@
+//! A headless `App` running the gameplay plugins, for tests.
+
+use bevy::{
+    app::Events,
+    input::{
+        gamepad::{GamepadEventRaw, GamepadEventType},
+        keyboard::KeyboardInput,
+        ElementState, InputPlugin,
+    },
+    prelude::*,
+};
+use tetris_core::{settings::GameSettings, Game};
+
+use crate::misc::states::AppState;
+
+use super::{
+    actions::ActionPlugin, collision::CollisionPlugin, movement::MovementPlugin, tick::TickPlugin,
+};
+
+/// Plays one tick per `update`, on a fresh game with the default settings.
+pub fn app(seed: u64) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .add_plugin(InputPlugin)
+        .add_plugin(ActionPlugin)
+        .add_plugin(TickPlugin { timestep: None })
+        .add_plugin(MovementPlugin)
+        .add_plugin(CollisionPlugin)
+        .insert_resource(Game::new(GameSettings::default(), seed))
+        .add_state(AppState::Moving);
+    app
+}
+
+fn key(app: &mut App, key_code: KeyCode, state: ElementState) {
+    app.world
+        .get_resource_mut::<Events<KeyboardInput>>()
+        .unwrap()
+        .send(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key_code),
+            state,
+        });
+}
+
+pub fn press(app: &mut App, key_code: KeyCode) {
+    key(app, key_code, ElementState::Pressed);
+}
+
+pub fn release(app: &mut App, key_code: KeyCode) {
+    key(app, key_code, ElementState::Released);
+}
+
+/// Sends a raw event from `gamepad`, as the gamepad backend would.
+pub fn pad(app: &mut App, gamepad: Gamepad, event: GamepadEventType) {
+    app.world
+        .get_resource_mut::<Events<GamepadEventRaw>>()
+        .unwrap()
+        .send(GamepadEventRaw(gamepad, event));
+}
+
+pub fn game(app: &App) -> &Game {
+    app.world.get_resource::<Game>().unwrap()
+}
\ No newline at end of file
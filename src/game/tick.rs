@@ -0,0 +1,54 @@
+use bevy::{core::FixedTimestep, ecs::schedule::ShouldRun, prelude::*};
+
+use tetris_core::settings::TICKS_PER_SECOND;
+
+use crate::misc::states::AppState;
+
+use super::actions::ActionSet;
+
+/// Stage the gameplay rules run in, once per fixed tick. It runs before
+/// `CoreStage::Update` so the state changes it asks for are applied, and
+/// drawn, in the same frame.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+pub struct GameTick;
+
+pub struct TickPlugin {
+    /// Seconds per tick, or `None` to play exactly one tick every frame so
+    /// tests don't depend on the wall clock.
+    pub timestep: Option<f64>,
+}
+
+impl Default for TickPlugin {
+    fn default() -> Self {
+        TickPlugin {
+            timestep: Some(1.0 / TICKS_PER_SECOND as f64),
+        }
+    }
+}
+
+impl Plugin for TickPlugin {
+    fn build(&self, app: &mut App) {
+        let stage = match self.timestep {
+            Some(timestep) => SystemStage::parallel().with_run_criteria(FixedTimestep::step(timestep)),
+            None => SystemStage::parallel(),
+        };
+        app
+            .add_stage_before(CoreStage::Update, GameTick, stage)
+            .add_system_to_stage(GameTick, end_tick.after("collision"));
+    }
+}
+
+/// Run criteria for systems in `GameTick` that only play while the piece is
+/// moving. `SystemSet::on_update` can't be used here: without the state
+/// driver in this stage its criteria ask to be checked again forever.
+pub fn playing(state: Res<State<AppState>>) -> ShouldRun {
+    if state.current() == &AppState::Moving {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+fn end_tick(mut actions: ResMut<ActionSet>) {
+    actions.end_tick();
+}
\ No newline at end of file
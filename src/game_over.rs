@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+use tetris_core::{game::SPLIT_LINES, settings::Mode, Game};
+
+use crate::{
+    game::actions::{MenuInput, MenuKey},
+    menu::START_FONT,
+    misc::states::AppState,
+    records::{format_delta, format_time, Records, SprintRecord},
+};
+
+const OVERLAY_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.7);
+const RESULT_FONT_SIZE: f32 = 16.0;
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_enter(AppState::GameOver)
+                    .with_system(setup_game_over)
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::GameOver)
+                    .with_system(user_input)
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::GameOver)
+                    .with_system(despawn_game_over)
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct GameOverScreen;
+
+/// Lines describing how the game went, keeping any new personal best. A
+/// sprint only counts if it was finished.
+fn results(game: &Game, records: &mut Records) -> Vec<String> {
+    match game.settings().mode {
+        Mode::Marathon { .. } => {
+            let score = game.score();
+            vec![
+                format!("SCORE {}", score.score),
+                format!("LEVEL {}", score.level()),
+                format!("LINES {}", score.lines),
+                format!("TIME {}", format_time(game.tick())),
+            ]
+        }
+        Mode::Sprint { lines } if game.finished() => sprint_results(game, records, lines),
+        Mode::Sprint { .. } => Vec::new(),
+        Mode::Ultra => ultra_results(game, records),
+        Mode::Zen => Vec::new(),
+        Mode::Master => vec![
+            format!("LEVEL {}", game.section_level().0),
+            format!("LINES {}", game.score().lines),
+            format!("TIME {}", format_time(game.tick())),
+        ],
+    }
+}
+
+fn sprint_results(game: &Game, records: &mut Records, lines: u32) -> Vec<String> {
+    let record = SprintRecord {
+        time: game.tick(),
+        splits: game.splits().to_vec(),
+    };
+    let best = records.sprint(lines).cloned();
+    let mut results = vec![format!("TIME {}", format_time(record.time))];
+    for (index, &split) in record.splits.iter().enumerate() {
+        let mut result = format!("{} {}", (index as u32 + 1) * SPLIT_LINES, format_time(split));
+        if let Some(best_split) = best.as_ref().and_then(|best| best.splits.get(index)) {
+            result += &format!(" {}", format_delta(split, *best_split));
+        }
+        results.push(result);
+    }
+    if records.submit_sprint(lines, record) {
+        results.push(String::from("NEW BEST"));
+    } else if let Some(best) = best {
+        results.push(format!("BEST {}", format_time(best.time)));
+    }
+    results
+}
+
+fn ultra_results(game: &Game, records: &mut Records) -> Vec<String> {
+    let score = game.score();
+    let seed = game.settings().seed;
+    let best = records.ultra(seed);
+    let mut results = vec![
+        format!("SCORE {}", score.score),
+        format!("LINES {}", score.lines),
+        format!("TETRISES {}", score.tetrises),
+        format!("T-SPINS {}", score.t_spins),
+        format!("MAX COMBO {}", score.max_combo),
+    ];
+    if records.submit_ultra(seed, score.score) {
+        results.push(String::from("NEW BEST"));
+    } else if let Some(best) = best {
+        results.push(format!("BEST {}", best));
+    }
+    results
+}
+
+fn setup_game_over(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game: Option<Res<Game>>,
+    mut records: ResMut<Records>,
+    mut buttons: ResMut<Input<GamepadButton>>,
+) {
+    // A button pressed while playing must not restart straight away.
+    buttons.clear();
+    let finished = game.as_ref().is_some_and(|game| game.finished());
+    let results = game.map_or_else(Vec::new, |game| results(&game, &mut records));
+    commands
+        .spawn_bundle(UiCameraBundle::default())
+        .insert(GameOverScreen);
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: OVERLAY_COLOR.into(),
+            ..Default::default()
+        })
+        .insert(GameOverScreen)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    style: Style {
+                        margin: Rect::all(Val::Px(20.0)),
+                        ..Default::default()
+                    },
+                    text: Text::with_section(
+                        if finished { "FINISHED" } else { "GAME OVER" },
+                        TextStyle {
+                            font: asset_server.load(START_FONT),
+                            font_size: 45.0,
+                            color: Color::WHITE,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                });
+            for result in results {
+                parent
+                    .spawn_bundle(TextBundle {
+                        style: Style {
+                            margin: Rect::all(Val::Px(5.0)),
+                            ..Default::default()
+                        },
+                        text: Text::with_section(
+                            result,
+                            TextStyle {
+                                font: asset_server.load(START_FONT),
+                                font_size: RESULT_FONT_SIZE,
+                                color: Color::WHITE,
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    });
+            }
+            parent
+                .spawn_bundle(TextBundle {
+                    style: Style {
+                        margin: Rect::all(Val::Px(20.0)),
+                        ..Default::default()
+                    },
+                    text: Text::with_section(
+                        "PRESS ENTER",
+                        TextStyle {
+                            font: asset_server.load(START_FONT),
+                            font_size: 25.0,
+                            color: Color::WHITE,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                });
+        });
+}
+
+fn user_input(
+    mut app_state: ResMut<State<AppState>>,
+    menu_input: MenuInput,
+) {
+    if menu_input.just_pressed(MenuKey::Confirm) {
+        app_state.set(AppState::Game).unwrap_or_default();
+    }
+}
+
+fn despawn_game_over(
+    mut commands: Commands,
+    game_over_query: Query<Entity, With<GameOverScreen>>,
+) {
+    for entity in game_over_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
\ No newline at end of file
@@ -1,17 +1,31 @@
+// Bevy systems take their resources and queries as arguments, which routinely
+// trips these lints.
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
 use bevy::prelude::*;
-use game::{collision::CollisionPlugin, movement::MovementPlugin, player::PlayerPlugin, setup_game::SetupGamePlugin};
+use game::{actions::ActionPlugin, block::BlockPlugin, collision::CollisionPlugin, ghost::GhostPlugin, hold::HoldPlugin, movement::MovementPlugin, player::PlayerPlugin, preview::PreviewPlugin, score::ScorePlugin, setup_game::SetupGamePlugin, tick::TickPlugin};
+use game_over::GameOverPlugin;
 use menu::MenuPlugin;
+use pause::PausePlugin;
 use misc::states::AppState;
+use records::Records;
+use tetris_core::settings::GameSettings;
 
 mod menu;
+mod controls;
+mod game_over;
+mod pause;
 mod game;
 mod misc;
+mod records;
 
 const BACKGROUND_COLOR: Color = Color::rgb(0.0, 0.0, 0.0);
 
 fn main() {
     App::new()
         .insert_resource(ClearColor(BACKGROUND_COLOR))
+        .insert_resource(GameSettings::from_args(std::env::args()))
+        .insert_resource(Records::load())
         .insert_resource(WindowDescriptor {
             title: String::from("Tetris"),
             width: 800.0, 
@@ -21,11 +35,19 @@ fn main() {
         }) 
         .add_plugins(DefaultPlugins)
         .add_plugin(MenuPlugin)
+        .add_plugin(ActionPlugin)
+        .add_plugin(TickPlugin::default())
+        .add_plugin(BlockPlugin)
         .add_plugin(MovementPlugin)
         .add_plugin(CollisionPlugin)
         .add_plugin(PlayerPlugin)
         .add_plugin(SetupGamePlugin)
+        .add_plugin(ScorePlugin)
+        .add_plugin(PreviewPlugin)
+        .add_plugin(HoldPlugin)
+        .add_plugin(GhostPlugin)
+        .add_plugin(GameOverPlugin)
+        .add_plugin(PausePlugin)
         .add_state(AppState::Menu)
-        .add_system(bevy::input::system::exit_on_esc_system)
         .run();
 }
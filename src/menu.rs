@@ -1,20 +1,35 @@
-use bevy::prelude::*;
+use bevy::{app::AppExit, prelude::*};
 
+use crate::controls::ControlsPlugin;
+use crate::game::actions::{MenuInput, MenuKey};
 use crate::misc::states::AppState;
+use tetris_core::{randomizer::RandomizerKind, settings::{GameSettings, Handling, LockReset, Mode, SoftDrop, MAX_PREVIEW, MAX_START_LEVEL}};
 
 const BACKGROUND_COLOR: Color = Color::rgb(0.0, 0.0, 0.0);
-const START_FONT: &str = "fonts/PressStart2P-vaV7.ttf";
+pub const START_FONT: &str = "fonts/PressStart2P-vaV7.ttf";
+/// Highlight of the option under the cursor, on every menu screen.
+pub const SELECTED_COLOR: Color = Color::rgb(1.0, 0.85, 0.0);
 const BLINK_TICK: f64 = 0.75;
+const OPTION_FONT_SIZE: f32 = 14.0;
+const DELAY_STEP: i32 = 10;
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_startup_system(setup_menu)
+            .init_resource::<GameSettings>()
+            .init_resource::<MenuCursor>()
+            .add_plugin(ControlsPlugin)
+            .add_system_set(
+                SystemSet::on_enter(AppState::Menu)
+                    .with_system(setup_menu)
+            )
             .add_system_set(
                 SystemSet::on_update(AppState::Menu)
                     .with_system(blink_text)
                     .with_system(user_input)
+                    .with_system(option_input.label("option_input"))
+                    .with_system(update_options.after("option_input"))
             )
             .add_system_set(
                 SystemSet::on_exit(AppState::Menu)
@@ -32,10 +47,152 @@ pub struct StartText;
 #[derive(Component)]
 pub struct LastUpdate(f64);
 
+/// A setting that can be changed from the menu with the arrow keys.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MenuOption {
+    Mode,
+    Level,
+    Randomizer,
+    Seed,
+    Preview,
+    Ghost,
+    SoftDrop,
+    Handling,
+    Das,
+    Arr,
+    Dcd,
+    LockDelay,
+    LockReset,
+    Are,
+    Controls,
+}
+
+impl MenuOption {
+    const ALL: [MenuOption; 15] = [
+        MenuOption::Mode,
+        MenuOption::Level,
+        MenuOption::Randomizer,
+        MenuOption::Seed,
+        MenuOption::Preview,
+        MenuOption::Ghost,
+        MenuOption::SoftDrop,
+        MenuOption::Handling,
+        MenuOption::Das,
+        MenuOption::Arr,
+        MenuOption::Dcd,
+        MenuOption::LockDelay,
+        MenuOption::LockReset,
+        MenuOption::Are,
+        MenuOption::Controls,
+    ];
+
+    fn label(&self, settings: &GameSettings) -> String {
+        match self {
+            MenuOption::Mode => format!("MODE {}", settings.mode.name()),
+            MenuOption::Level => format!("LEVEL {}", settings.start_level),
+            MenuOption::Randomizer => format!("RANDOMIZER {}", settings.randomizer.name()),
+            MenuOption::Seed => match settings.seed {
+                Some(seed) => format!("SEED {}", seed),
+                None => String::from("SEED RANDOM"),
+            },
+            MenuOption::Preview => format!("NEXT {}", settings.preview),
+            MenuOption::Ghost => format!("GHOST {}", if settings.ghost { "ON" } else { "OFF" }),
+            MenuOption::SoftDrop => format!("SOFT DROP {}", settings.soft_drop.name()),
+            MenuOption::Handling => format!("HANDLING {}", settings.handling.profile_name()),
+            MenuOption::Das => format!("DAS {}MS", settings.handling.das),
+            MenuOption::Arr => format!("ARR {}MS", settings.handling.arr),
+            MenuOption::Dcd => format!("DAS CUT {}MS", settings.handling.dcd),
+            MenuOption::LockDelay => format!("LOCK DELAY {}MS", settings.lock_delay),
+            MenuOption::LockReset => format!("LOCK RESET {}", settings.lock_reset.name()),
+            MenuOption::Are => format!("ARE {}MS", settings.are),
+            MenuOption::Controls => String::from("CONTROLS"),
+        }
+    }
+
+    /// Steps the setting forwards or backwards through its values.
+    fn change(&self, settings: &mut GameSettings, step: i32) {
+        match self {
+            MenuOption::Mode => settings.mode = cycle(&Mode::ALL, settings.mode, step),
+            MenuOption::Level => {
+                let levels: Vec<u32> = (1..=MAX_START_LEVEL).collect();
+                settings.start_level = cycle(&levels, settings.start_level, step);
+            }
+            MenuOption::Randomizer => {
+                settings.randomizer = cycle(&RandomizerKind::ALL, settings.randomizer, step);
+            }
+            MenuOption::Seed => (),
+            MenuOption::Preview => {
+                let previews: Vec<usize> = (0..=MAX_PREVIEW).collect();
+                settings.preview = cycle(&previews, settings.preview, step);
+            }
+            MenuOption::Ghost => settings.ghost = !settings.ghost,
+            MenuOption::SoftDrop => {
+                settings.soft_drop = cycle(&SoftDrop::ALL, settings.soft_drop, step);
+            }
+            MenuOption::Handling => {
+                let profiles: Vec<Handling> = Handling::PROFILES.iter().map(|(_, handling)| *handling).collect();
+                settings.handling = cycle(&profiles, settings.handling, step);
+            }
+            MenuOption::Das => adjust_delay(&mut settings.handling.das, step),
+            MenuOption::Arr => adjust_delay(&mut settings.handling.arr, step),
+            MenuOption::Dcd => adjust_delay(&mut settings.handling.dcd, step),
+            MenuOption::LockDelay => adjust_delay(&mut settings.lock_delay, step * 5),
+            MenuOption::LockReset => {
+                settings.lock_reset = cycle(&LockReset::ALL, settings.lock_reset, step);
+            }
+            MenuOption::Are => adjust_delay(&mut settings.are, step),
+            MenuOption::Controls => (),
+        }
+    }
+}
+
+/// Steps a millisecond delay by `DELAY_STEP`, within `0..=Handling::MAX_DELAY`.
+fn adjust_delay(delay: &mut u32, step: i32) {
+    let adjusted = *delay as i32 + step * DELAY_STEP;
+    *delay = adjusted.clamp(0, Handling::MAX_DELAY as i32) as u32;
+}
+
+pub fn cycle<T: Copy + PartialEq>(values: &[T], current: T, step: i32) -> T {
+    let index = values.iter().position(|&value| value == current).unwrap_or(0) as i32;
+    values[(index + step).rem_euclid(values.len() as i32) as usize]
+}
+
+/// Index into `MenuOption::ALL` of the highlighted option.
+#[derive(Default)]
+pub struct MenuCursor(usize);
+
+#[derive(Component)]
+pub struct OptionText(MenuOption);
+
+/// A line of white menu text with the usual spacing around it.
+pub fn text_bundle(asset_server: &AssetServer, value: &str, font_size: f32) -> TextBundle {
+    TextBundle {
+        style: Style {
+            margin: Rect::all(Val::Px(5.0)),
+            ..Default::default()
+        },
+        text: Text::with_section(
+            value,
+            TextStyle {
+                font: asset_server.load(START_FONT),
+                font_size,
+                color: Color::WHITE,
+            },
+            Default::default(),
+        ),
+        ..Default::default()
+    }
+}
+
 fn setup_menu(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut buttons: ResMut<Input<GamepadButton>>,
 ) {
+    // The key that led back here must not start a game straight away.
+    keys.clear();
+    buttons.clear();
     commands
         .spawn_bundle(UiCameraBundle::default())
         .insert(LastUpdate(0.0))
@@ -45,6 +202,7 @@ fn setup_menu(
         .spawn_bundle(NodeBundle {
             style: Style {
                 size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
                 justify_content: JustifyContent::Center,
                 align_self: AlignSelf::Center,
                 align_items: AlignItems::Center,
@@ -55,6 +213,14 @@ fn setup_menu(
         })
         .insert(MainMenu)
         .with_children(|parent| {
+            let mut title = text_bundle(&asset_server, "TETRIS", 45.0);
+            title.style.margin = Rect::all(Val::Px(30.0));
+            parent.spawn_bundle(title);
+            for option in MenuOption::ALL {
+                parent
+                    .spawn_bundle(text_bundle(&asset_server, "", OPTION_FONT_SIZE))
+                    .insert(OptionText(option));
+            }
             parent
                 .spawn_bundle(TextBundle {
                     style: Style {
@@ -63,6 +229,7 @@ fn setup_menu(
                         align_items: AlignItems::Center,
                         justify_content: JustifyContent::Center,
                         position_type: PositionType::Relative,
+                        margin: Rect::all(Val::Px(30.0)),
                         ..Default::default()
                     },
                     text: Text::with_section(
@@ -80,19 +247,6 @@ fn setup_menu(
                     ..Default::default()
                 })
                 .insert(StartText);
-            parent 
-                .spawn_bundle(TextBundle {
-                    text: Text::with_section(
-                        "TETRIS",
-                        TextStyle {
-                            font: asset_server.load(START_FONT),
-                            font_size: 45.0,
-                            color: Color::WHITE,
-                        },
-                        Default::default(),
-                    ),
-                    ..Default::default()
-                });
         });
 }
 
@@ -117,9 +271,87 @@ fn blink_text(
 fn user_input(
     mut app_state: ResMut<State<AppState>>,
     keys: Res<Input<KeyCode>>,
+    menu_input: MenuInput,
+    cursor: Res<MenuCursor>,
+    mut app_exit: EventWriter<AppExit>,
 ) {
-    if keys.just_pressed(KeyCode::Return) {
-        app_state.set(AppState::Game).unwrap_or_default();
+    if keys.just_pressed(KeyCode::Escape) {
+        app_exit.send(AppExit);
+    } else if menu_input.just_pressed(MenuKey::Confirm) {
+        if MenuOption::ALL[cursor.0] == MenuOption::Controls {
+            app_state.set(AppState::Controls).unwrap_or_default();
+        } else {
+            app_state.set(AppState::Game).unwrap_or_default();
+        }
+    }
+}
+
+fn option_input(
+    keys: Res<Input<KeyCode>>,
+    menu_input: MenuInput,
+    mut cursor: ResMut<MenuCursor>,
+    mut settings: ResMut<GameSettings>,
+) {
+    let count = MenuOption::ALL.len();
+    if menu_input.just_pressed(MenuKey::Down) {
+        cursor.0 = (cursor.0 + 1) % count;
+    } else if menu_input.just_pressed(MenuKey::Up) {
+        cursor.0 = (cursor.0 + count - 1) % count;
+    } else if menu_input.just_pressed(MenuKey::Right) {
+        MenuOption::ALL[cursor.0].change(&mut settings, 1);
+    } else if menu_input.just_pressed(MenuKey::Left) {
+        MenuOption::ALL[cursor.0].change(&mut settings, -1);
+    }
+
+    if MenuOption::ALL[cursor.0] == MenuOption::Seed {
+        type_seed(&keys, &mut settings.seed);
+    }
+}
+
+/// Edits the seed with the number keys, backspacing it to empty picks a
+/// random seed again.
+fn type_seed(keys: &Input<KeyCode>, seed: &mut Option<u64>) {
+    if keys.just_pressed(KeyCode::Back) {
+        *seed = seed.map(|seed| seed / 10).filter(|&seed| seed > 0);
+    }
+    for key in keys.get_just_pressed() {
+        if let Some(digit) = digit(*key) {
+            *seed = Some(seed.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+        }
+    }
+}
+
+fn digit(key: KeyCode) -> Option<u64> {
+    let digit = match key {
+        KeyCode::Key0 | KeyCode::Numpad0 => 0,
+        KeyCode::Key1 | KeyCode::Numpad1 => 1,
+        KeyCode::Key2 | KeyCode::Numpad2 => 2,
+        KeyCode::Key3 | KeyCode::Numpad3 => 3,
+        KeyCode::Key4 | KeyCode::Numpad4 => 4,
+        KeyCode::Key5 | KeyCode::Numpad5 => 5,
+        KeyCode::Key6 | KeyCode::Numpad6 => 6,
+        KeyCode::Key7 | KeyCode::Numpad7 => 7,
+        KeyCode::Key8 | KeyCode::Numpad8 => 8,
+        KeyCode::Key9 | KeyCode::Numpad9 => 9,
+        _ => return None,
+    };
+    Some(digit)
+}
+
+fn update_options(
+    cursor: Res<MenuCursor>,
+    settings: Res<GameSettings>,
+    mut option_query: Query<(&OptionText, &mut Text)>,
+) {
+    for (option, mut text) in option_query.iter_mut() {
+        let selected = MenuOption::ALL[cursor.0] == option.0;
+        let label = option.0.label(&settings);
+        text.sections[0].value = if selected {
+            format!("< {} >", label)
+        } else {
+            label
+        };
+        text.sections[0].style.color = if selected { SELECTED_COLOR } else { Color::WHITE };
     }
 }
 
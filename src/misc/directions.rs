@@ -1,7 +0,0 @@
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub enum Direction {
-    Left,
-    Right,
-    Down,
-    SuperDown,
-}
\ No newline at end of file
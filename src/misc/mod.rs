@@ -1,3 +1 @@
-pub mod states;
-pub mod directions;
-pub mod shapes;
\ No newline at end of file
+pub mod states;
\ No newline at end of file
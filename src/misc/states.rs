@@ -1,7 +1,9 @@
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum AppState {
     Menu,
+    Controls,
     Game,
     Moving,
-    Still,
+    Paused,
+    GameOver,
 }
\ No newline at end of file
@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+
+use crate::{
+    game::actions::{Action, ActionSet, MenuInput, MenuKey},
+    menu::{text_bundle, SELECTED_COLOR},
+    misc::states::AppState,
+};
+
+/// Opaque, so the stack can't be studied while paused.
+const OVERLAY_COLOR: Color = Color::rgb(0.0, 0.0, 0.0);
+const OPTION_FONT_SIZE: f32 = 14.0;
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<PauseCursor>()
+            .add_system_set(
+                SystemSet::on_update(AppState::Moving)
+                    .with_system(pause_input)
+            )
+            .add_system_set(
+                SystemSet::on_resume(AppState::Moving)
+                    .with_system(swallow_input)
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::Paused)
+                    .with_system(reset_cursor)
+                    .with_system(swallow_input)
+                    .with_system(setup_pause)
+            )
+            .add_system_set(
+                SystemSet::on_resume(AppState::Paused)
+                    .with_system(setup_pause)
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Paused)
+                    .with_system(user_input.label("pause_input"))
+                    .with_system(update_options.after("pause_input"))
+            )
+            .add_system_set(
+                SystemSet::on_pause(AppState::Paused)
+                    .with_system(despawn_pause)
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Paused)
+                    .with_system(despawn_pause)
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct PauseScreen;
+
+#[derive(Component, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PauseOption {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+impl PauseOption {
+    const ALL: [PauseOption; 4] = [
+        PauseOption::Resume,
+        PauseOption::Restart,
+        PauseOption::Settings,
+        PauseOption::Quit,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            PauseOption::Resume => "RESUME",
+            PauseOption::Restart => "RESTART",
+            PauseOption::Settings => "SETTINGS",
+            PauseOption::Quit => "QUIT TO MENU",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PauseCursor(usize);
+
+fn pause_input(
+    mut app_state: ResMut<State<AppState>>,
+    keys: Res<Input<KeyCode>>,
+    actions: Res<ActionSet>,
+) {
+    if keys.just_pressed(KeyCode::Escape) || actions.just_pressed(Action::Pause) {
+        app_state.push(AppState::Paused).unwrap_or_default();
+    }
+}
+
+/// States switch within the frame, so the key that paused or resumed the
+/// game would otherwise toggle it straight back.
+fn swallow_input(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut buttons: ResMut<Input<GamepadButton>>,
+    mut actions: ResMut<ActionSet>,
+) {
+    keys.clear();
+    buttons.clear();
+    actions.clear_pressed();
+}
+
+fn reset_cursor(mut cursor: ResMut<PauseCursor>) {
+    cursor.0 = 0;
+}
+
+fn setup_pause(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands
+        .spawn_bundle(UiCameraBundle::default())
+        .insert(PauseScreen);
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: OVERLAY_COLOR.into(),
+            ..Default::default()
+        })
+        .insert(PauseScreen)
+        .with_children(|parent| {
+            let mut title = text_bundle(&asset_server, "PAUSED", 45.0);
+            title.style.margin = Rect::all(Val::Px(20.0));
+            parent.spawn_bundle(title);
+            for option in PauseOption::ALL {
+                parent
+                    .spawn_bundle(text_bundle(&asset_server, option.label(), OPTION_FONT_SIZE))
+                    .insert(option);
+            }
+        });
+}
+
+fn user_input(
+    mut app_state: ResMut<State<AppState>>,
+    keys: Res<Input<KeyCode>>,
+    menu_input: MenuInput,
+    actions: Res<ActionSet>,
+    mut cursor: ResMut<PauseCursor>,
+) {
+    let count = PauseOption::ALL.len();
+    if keys.just_pressed(KeyCode::Escape) || actions.just_pressed(Action::Pause) {
+        app_state.pop().unwrap_or_default();
+    } else if menu_input.just_pressed(MenuKey::Down) {
+        cursor.0 = (cursor.0 + 1) % count;
+    } else if menu_input.just_pressed(MenuKey::Up) {
+        cursor.0 = (cursor.0 + count - 1) % count;
+    } else if menu_input.just_pressed(MenuKey::Confirm) {
+        match PauseOption::ALL[cursor.0] {
+            PauseOption::Resume => app_state.pop(),
+            PauseOption::Restart => app_state.replace(AppState::Game),
+            PauseOption::Settings => app_state.push(AppState::Controls),
+            PauseOption::Quit => app_state.replace(AppState::Menu),
+        }
+        .unwrap_or_default();
+    }
+}
+
+fn update_options(
+    cursor: Res<PauseCursor>,
+    mut option_query: Query<(&PauseOption, &mut Text)>,
+) {
+    for (option, mut text) in option_query.iter_mut() {
+        let selected = PauseOption::ALL[cursor.0] == *option;
+        text.sections[0].value = if selected {
+            format!("< {} >", option.label())
+        } else {
+            option.label().to_string()
+        };
+        text.sections[0].style.color = if selected { SELECTED_COLOR } else { Color::WHITE };
+    }
+}
+
+fn despawn_pause(
+    mut commands: Commands,
+    pause_query: Query<Entity, With<PauseScreen>>,
+) {
+    for entity in pause_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
\ No newline at end of file
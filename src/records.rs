@@ -0,0 +1,156 @@
+use std::{collections::HashMap, fs};
+
+use tetris_core::settings::millis;
+
+/// Where personal bests are kept between runs, next to the executable's
+/// working directory.
+const RECORDS_PATH: &str = "records.txt";
+
+/// The fastest finish of a sprint and the splits it went through.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SprintRecord {
+    /// Ticks from the first piece to the last line.
+    pub time: u64,
+    pub splits: Vec<u64>,
+}
+
+/// Personal bests, one line per record in `RECORDS_PATH`:
+/// `sprint <lines> <time> <splits>...`, all times in ticks, or
+/// `ultra <seed|random> <score>`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Records {
+    sprint: HashMap<u32, SprintRecord>,
+    /// Best ultra score for each seed setting, `None` being a random seed.
+    ultra: HashMap<Option<u64>, u32>,
+}
+
+impl Records {
+    /// Reads the saved records, starting afresh if there are none.
+    pub fn load() -> Self {
+        fs::read_to_string(RECORDS_PATH)
+            .map(|text| Records::parse(&text))
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        if let Err(error) = fs::write(RECORDS_PATH, self.to_text()) {
+            eprintln!("could not save records: {}", error);
+        }
+    }
+
+    /// Reads records from their text form, skipping lines it can't make sense of.
+    fn parse(text: &str) -> Self {
+        let mut records = Records::default();
+        for line in text.lines() {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("sprint") => {
+                    let numbers: Option<Vec<u64>> = fields.map(|field| field.parse().ok()).collect();
+                    if let Some([lines, time, splits @ ..]) = numbers.as_deref() {
+                        let record = SprintRecord {
+                            time: *time,
+                            splits: splits.to_vec(),
+                        };
+                        records.sprint.insert(*lines as u32, record);
+                    }
+                }
+                Some("ultra") => {
+                    let seed = match fields.next() {
+                        Some("random") => Some(None),
+                        seed => seed.and_then(|seed| seed.parse().ok()).map(Some),
+                    };
+                    let score = fields.next().and_then(|score| score.parse().ok());
+                    if let (Some(seed), Some(score)) = (seed, score) {
+                        records.ultra.insert(seed, score);
+                    }
+                }
+                _ => (),
+            }
+        }
+        records
+    }
+
+    fn to_text(&self) -> String {
+        let mut sprints: Vec<_> = self.sprint.iter().collect();
+        sprints.sort_by_key(|(lines, _)| **lines);
+        let mut ultras: Vec<_> = self.ultra.iter().collect();
+        ultras.sort_by_key(|(seed, _)| **seed);
+
+        let sprints = sprints.into_iter().map(|(lines, record)| {
+            let splits: Vec<String> = record.splits.iter().map(u64::to_string).collect();
+            format!("sprint {} {} {}\n", lines, record.time, splits.join(" "))
+        });
+        let ultras = ultras.into_iter().map(|(seed, score)| {
+            let seed = seed.map_or_else(|| String::from("random"), |seed| seed.to_string());
+            format!("ultra {} {}\n", seed, score)
+        });
+        sprints.chain(ultras).collect()
+    }
+
+    pub fn sprint(&self, lines: u32) -> Option<&SprintRecord> {
+        self.sprint.get(&lines)
+    }
+
+    /// Keeps `record` and saves it if it beats the best sprint over `lines`,
+    /// returning whether it did.
+    pub fn submit_sprint(&mut self, lines: u32, record: SprintRecord) -> bool {
+        if self.sprint(lines).is_some_and(|best| best.time <= record.time) {
+            return false;
+        }
+        self.sprint.insert(lines, record);
+        self.save();
+        true
+    }
+
+    pub fn ultra(&self, seed: Option<u64>) -> Option<u32> {
+        self.ultra.get(&seed).copied()
+    }
+
+    /// Keeps `score` and saves it if it beats the best ultra score on the
+    /// `seed` setting, returning whether it did.
+    pub fn submit_ultra(&mut self, seed: Option<u64>, score: u32) -> bool {
+        if self.ultra(seed).is_some_and(|best| best >= score) {
+            return false;
+        }
+        self.ultra.insert(seed, score);
+        self.save();
+        true
+    }
+}
+
+/// Formats a tick count as `m:ss.mmm`.
+pub fn format_time(ticks: u64) -> String {
+    let ms = millis(ticks);
+    format!("{}:{:02}.{:03}", ms / 60_000, ms / 1000 % 60, ms % 1000)
+}
+
+/// Formats how far `ticks` is ahead (`-`) or behind (`+`) of `best`, in seconds.
+pub fn format_delta(ticks: u64, best: u64) -> String {
+    let sign = if ticks < best { '-' } else { '+' };
+    let ms = millis(ticks.max(best) - ticks.min(best));
+    format!("{}{}.{:03}", sign, ms / 1000, ms % 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_survive_a_round_trip() {
+        let mut records = Records::default();
+        records.sprint.insert(40, SprintRecord { time: 3600, splits: vec![900, 1800, 2700, 3600] });
+        records.sprint.insert(20, SprintRecord { time: 1500, splits: vec![700, 1500] });
+        records.ultra.insert(None, 52000);
+        records.ultra.insert(Some(42), 61000);
+
+        assert_eq!(Records::parse(&records.to_text()), records);
+        assert_eq!(Records::parse("sprint 40 x\nnonsense\n"), Records::default());
+    }
+
+    #[test]
+    fn formats_times_and_deltas() {
+        assert_eq!(format_time(3725), "1:02.083");
+        assert_eq!(format_delta(90, 120), "-0.500");
+        assert_eq!(format_delta(150, 90), "+1.000");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,266 @@
+use crate::directions::Rotation;
+
+use super::{piece::Piece, rotation::kicks};
+
+pub const BOARD_WIDTH: i32 = 10;
+pub const VISIBLE_HEIGHT: i32 = 20;
+/// Visible rows plus the hidden rows pieces spawn in.
+pub const BOARD_HEIGHT: i32 = 40;
+
+/// The playfield grid. Each locked cell stores the tile index it was drawn
+/// with; `(0, 0)` is the bottom-left cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Board {
+    cells: Vec<Option<usize>>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board {
+            cells: vec![None; (BOARD_WIDTH * BOARD_HEIGHT) as usize],
+        }
+    }
+}
+
+impl Board {
+    fn index(x: i32, y: i32) -> Option<usize> {
+        if (0..BOARD_WIDTH).contains(&x) && (0..BOARD_HEIGHT).contains(&y) {
+            Some((y * BOARD_WIDTH + x) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `(x, y)` is inside the board and not occupied.
+    pub fn is_free(&self, x: i32, y: i32) -> bool {
+        matches!(Board::index(x, y), Some(index) if self.cells[index].is_none())
+    }
+
+    pub fn fits(&self, piece: &Piece) -> bool {
+        piece.cells().iter().all(|&(x, y)| self.is_free(x, y))
+    }
+
+    /// Rows `piece` can fall before it rests on the floor or the stack.
+    pub fn drop_distance(&self, piece: &Piece) -> i32 {
+        let mut distance = 0;
+        while self.fits(&piece.shifted(0, -distance - 1)) {
+            distance += 1;
+        }
+        distance
+    }
+
+    /// Where `piece` lands if dropped straight down.
+    pub fn landing(&self, piece: &Piece) -> Piece {
+        piece.shifted(0, -self.drop_distance(piece))
+    }
+
+    /// Moves `piece` by `(dx, dy)` if it fits there, returning whether it moved.
+    pub fn try_shift(&self, piece: &mut Piece, dx: i32, dy: i32) -> bool {
+        let shifted = piece.shifted(dx, dy);
+        if self.fits(&shifted) {
+            *piece = shifted;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Rotates `piece`, trying each wall kick in turn, and returns whether any
+    /// of them fit.
+    pub fn try_rotate(&self, piece: &mut Piece, rotation: Rotation) -> bool {
+        let rotated = piece.rotated(rotation);
+        for &(dx, dy) in kicks(piece.shape, piece.rotation, rotation) {
+            let kicked = rotated.shifted(dx, dy);
+            if self.fits(&kicked) {
+                *piece = kicked;
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn lock(&mut self, piece: &Piece, tile: usize) {
+        for (x, y) in piece.cells() {
+            if let Some(index) = Board::index(x, y) {
+                self.cells[index] = Some(tile);
+            }
+        }
+    }
+
+    fn is_row_full(&self, y: i32) -> bool {
+        (0..BOARD_WIDTH).all(|x| !self.is_free(x, y))
+    }
+
+    pub fn has_full_row(&self) -> bool {
+        (0..BOARD_HEIGHT).any(|y| self.is_row_full(y))
+    }
+
+    /// Removes every full row and drops the rows above it, returning the
+    /// indices of the removed rows from the bottom up.
+    pub fn clear_full_rows(&mut self) -> Vec<i32> {
+        let full_rows: Vec<i32> = (0..BOARD_HEIGHT).filter(|&y| self.is_row_full(y)).collect();
+        if full_rows.is_empty() {
+            return full_rows;
+        }
+
+        let width = BOARD_WIDTH as usize;
+        let mut cells: Vec<Option<usize>> = self.cells
+            .chunks(width)
+            .enumerate()
+            .filter(|(y, _)| !full_rows.contains(&(*y as i32)))
+            .flat_map(|(_, row)| row.iter().copied())
+            .collect();
+        cells.resize(self.cells.len(), None);
+        self.cells = cells;
+        full_rows
+    }
+
+    /// A board holding just the `filled` cells, for setting up tests.
+    #[cfg(test)]
+    pub(crate) fn with_cells(filled: &[(i32, i32)]) -> Self {
+        let mut board = Board::default();
+        for &(x, y) in filled {
+            board.cells[Board::index(x, y).unwrap()] = Some(0);
+        }
+        board
+    }
+
+    /// Occupied cells with their tile index.
+    pub fn occupied(&self) -> impl Iterator<Item = ((i32, i32), usize)> + '_ {
+        self.cells.iter().enumerate().filter_map(|(index, cell)| {
+            let index = index as i32;
+            cell.map(|tile| ((index % BOARD_WIDTH, index / BOARD_WIDTH), tile))
+        })
+    }
+}
+
+/// Every cell of rows `0..height` but the ones in column `gap`, for setting
+/// up tests.
+#[cfg(test)]
+pub(crate) fn stack(height: i32, gap: i32) -> Vec<(i32, i32)> {
+    (0..height)
+        .flat_map(|y| (0..BOARD_WIDTH).map(move |x| (x, y)))
+        .filter(|&(x, _)| x != gap)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::Shape;
+
+    fn slide(board: &Board, mut piece: Piece, dx: i32) -> usize {
+        let mut moves = 0;
+        while board.try_shift(&mut piece, dx, 0) {
+            moves += 1;
+        }
+        moves
+    }
+
+    #[test]
+    fn shifts_stop_at_both_walls() {
+        let cases = [
+            (Shape::IBlock, 3, 3),
+            (Shape::OBlock, 4, 4),
+            (Shape::TBlock, 3, 4),
+            (Shape::SBlock, 3, 4),
+            (Shape::ZBlock, 3, 4),
+            (Shape::LBlock, 3, 4),
+            (Shape::JBlock, 3, 4),
+        ];
+        let board = Board::default();
+        for (shape, left, right) in cases {
+            let piece = Piece::spawn(shape);
+            assert_eq!(slide(&board, piece, -1), left, "{:?} left", shape);
+            assert_eq!(slide(&board, piece, 1), right, "{:?} right", shape);
+        }
+    }
+
+    #[test]
+    fn shifts_stop_at_the_stack() {
+        let board = Board::with_cells(&[(0, 20), (9, 20)]);
+        let piece = Piece::spawn(Shape::TBlock);
+        assert_eq!(slide(&board, piece, -1), 2);
+        assert_eq!(slide(&board, piece, 1), 3);
+    }
+
+    #[test]
+    fn every_shape_falls_to_the_floor() {
+        let board = Board::default();
+        for shape in Shape::ALL {
+            let landing = board.landing(&Piece::spawn(shape));
+            let bottom = landing.cells().iter().map(|cell| cell.1).min();
+            assert_eq!(bottom, Some(0), "{:?}", shape);
+            assert!(!board.fits(&landing.shifted(0, -1)), "{:?}", shape);
+        }
+    }
+
+    #[test]
+    fn pieces_land_on_the_stack() {
+        let cases = [
+            (Shape::TBlock, vec![(4, 5)], 14),
+            (Shape::IBlock, vec![(6, 0)], 19),
+            (Shape::OBlock, vec![(5, 9)], 10),
+            (Shape::SBlock, vec![(3, 3), (4, 3)], 16),
+            (Shape::JBlock, stack(4, 0), 16),
+        ];
+        for (shape, filled, distance) in cases {
+            let board = Board::with_cells(&filled);
+            assert_eq!(board.drop_distance(&Piece::spawn(shape)), distance, "{:?}", shape);
+        }
+    }
+
+    #[test]
+    fn rotations_kick_off_walls_and_floor() {
+        let cases = [
+            // Free rotation keeps the origin.
+            (Piece { shape: Shape::TBlock, rotation: 0, origin: (3, 5) }, Rotation::Clockwise, vec![], Some((1, (3, 5)))),
+            // T against the left wall kicks right.
+            (Piece { shape: Shape::TBlock, rotation: 1, origin: (-1, 5) }, Rotation::CounterClockwise, vec![], Some((0, (0, 5)))),
+            // I against the right wall kicks left.
+            (Piece { shape: Shape::IBlock, rotation: 1, origin: (7, 5) }, Rotation::CounterClockwise, vec![], Some((0, (6, 5)))),
+            // T on the floor kicks up and left.
+            (Piece { shape: Shape::TBlock, rotation: 0, origin: (4, -1) }, Rotation::Clockwise, vec![], Some((1, (3, 0)))),
+            // I at the bottom of a one-wide well cannot turn at all.
+            (Piece { shape: Shape::IBlock, rotation: 1, origin: (-2, 0) }, Rotation::Clockwise, stack(4, 0), None),
+        ];
+        for (piece, rotation, filled, expected) in cases {
+            let board = Board::with_cells(&filled);
+            let mut rotated = piece;
+            let turned = board.try_rotate(&mut rotated, rotation);
+            match expected {
+                Some((state, origin)) => {
+                    assert!(turned, "{:?}", piece);
+                    assert_eq!((rotated.rotation, rotated.origin), (state, origin), "{:?}", piece);
+                }
+                None => {
+                    assert!(!turned, "{:?}", piece);
+                    assert_eq!(rotated, piece);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn clears_one_to_four_rows() {
+        for count in 1..=4 {
+            let mut filled = stack(count, 9);
+            filled.extend((0..count).map(|y| (9, y)));
+            filled.push((0, count));
+            let mut board = Board::with_cells(&filled);
+
+            assert!(board.has_full_row());
+            assert_eq!(board.clear_full_rows(), (0..count).collect::<Vec<_>>());
+            assert!(!board.has_full_row());
+            assert_eq!(board.occupied().map(|(cell, _)| cell).collect::<Vec<_>>(), vec![(0, 0)]);
+        }
+    }
+
+    #[test]
+    fn leaves_rows_with_a_gap() {
+        let mut board = Board::with_cells(&stack(3, 5));
+        assert!(!board.has_full_row());
+        assert!(board.clear_full_rows().is_empty());
+        assert_eq!(board.occupied().count(), 27);
+    }
+}
\ No newline at end of file
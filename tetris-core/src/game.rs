@@ -0,0 +1,1060 @@
+use std::collections::VecDeque;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    board::{Board, VISIBLE_HEIGHT},
+    directions::{Direction, Rotation},
+    master::{SectionLevel, MASTER_MAX_LEVEL},
+    piece::Piece,
+    randomizer::Randomizer,
+    score::{level_gravity, ScoreState, TWENTY_G, TWENTY_G_LEVEL},
+    settings::{ticks, GameSettings, LockReset, Mode, SoftDrop, MARATHON_LINES, MAX_LOCK_RESETS},
+    shapes::Shape,
+};
+
+/// Colours in the tile sheet; the tile index of colour `n` is `n * 5`.
+const TILE_COLOURS: usize = 6;
+
+/// Lines cleared between two splits.
+pub const SPLIT_LINES: u32 = 10;
+
+/// What the player asks of a single tick. Presses are only set on the tick
+/// they happen, held inputs on every tick they are held.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Inputs {
+    /// Held horizontal direction. When both are held the caller picks one.
+    pub horizontal: Option<Direction>,
+    pub soft_drop: bool,
+    pub hard_drop: bool,
+    pub rotation: Option<Rotation>,
+    pub hold: bool,
+    /// Take back the last lock, in Zen mode.
+    pub undo: bool,
+}
+
+/// How a game ended.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TopOut {
+    /// A new piece overlapped the stack.
+    BlockOut,
+    /// A piece locked entirely above the visible playfield.
+    LockOut,
+}
+
+/// Something that happened during a tick, in the order it happened.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    /// A new piece entered the board.
+    Spawned(Shape),
+    /// The active piece was swapped into the hold slot.
+    Held,
+    /// The active piece locked where it stands.
+    Locked(Piece),
+    /// Rows removed by the last lock, from the bottom up.
+    LinesCleared(Vec<i32>),
+    GameOver(TopOut),
+    /// The mode's goal was reached, which also ends the game.
+    Finished,
+    /// Zen topped out, so the board was wiped and play carries on.
+    BoardWiped(TopOut),
+    /// The last lock was taken back, its piece returned to the top.
+    Undone,
+}
+
+/// Lock delay bookkeeping for the active piece.
+#[derive(Debug, Clone)]
+struct LockState {
+    /// Tick the lock delay last (re)started.
+    started: u64,
+    /// Move resets used since the piece last reached a new lowest row.
+    resets: u32,
+    lowest_row: i32,
+    touched: bool,
+    /// Share of the lock delay left.
+    remaining: f32,
+}
+
+impl LockState {
+    fn new(now: u64) -> Self {
+        LockState {
+            started: now,
+            resets: 0,
+            lowest_row: i32::MAX,
+            touched: false,
+            remaining: 1.0,
+        }
+    }
+
+    /// Whether a successful move or rotation restarts the lock delay, using
+    /// up one of the move resets if so.
+    fn try_move_reset(&mut self, lock_reset: LockReset, grounded: bool) -> bool {
+        if lock_reset == LockReset::Move && grounded && self.resets < MAX_LOCK_RESETS {
+            self.resets += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Delayed auto shift state of the held horizontal direction.
+#[derive(Debug, Clone, Default)]
+struct AutoShift {
+    direction: Option<Direction>,
+    /// Tick the next repeated shift is due, one DAS after the press and then
+    /// one ARR after each repeat.
+    repeat_at: u64,
+    /// Auto-repeat is held off until this tick after a rotation.
+    cut_until: u64,
+}
+
+/// A single game, advanced one fixed tick at a time by [`Game::step`].
+#[derive(Clone)]
+pub struct Game {
+    settings: GameSettings,
+    seed: u64,
+    rng: StdRng,
+    randomizer: Box<dyn Randomizer>,
+    board: Board,
+    /// Upcoming pieces, front first, each with the tile index it is drawn with.
+    next: VecDeque<(Shape, usize)>,
+    active: Option<Piece>,
+    tile: usize,
+    hold: Option<(Shape, usize)>,
+    /// Whether the hold may be used before the next lock.
+    hold_available: bool,
+    score: ScoreState,
+    /// Master mode level counter, left at `0` in the other modes.
+    section_level: SectionLevel,
+    tick: u64,
+    /// Tick the next piece enters, while waiting out the entry delay.
+    spawn_at: Option<u64>,
+    /// Ticks of gravity or soft drop built up towards the next row.
+    fall_progress: f64,
+    /// Ticks per row the progress was last built up at.
+    fall_interval: f64,
+    /// Whether the active piece is resting on the floor or the stack.
+    grounded: bool,
+    /// Whether the active piece's last move was a rotation, for T-spins.
+    spun: bool,
+    lock: LockState,
+    auto_shift: AutoShift,
+    top_out: Option<TopOut>,
+    finished: bool,
+    /// Tick each multiple of `SPLIT_LINES` lines was reached.
+    splits: Vec<u64>,
+    /// Zen gravity level, `0..=TWENTY_G_LEVEL`.
+    zen_gravity: u32,
+    /// Zen snapshot of the game as the active piece entered.
+    entered: Option<Box<Game>>,
+    /// Zen snapshots of the game as each locked piece entered, the last lock
+    /// last.
+    history: Vec<Game>,
+}
+
+impl Game {
+    /// Starts a game with the first piece already on the board. The same
+    /// settings, seed and inputs always play out the same way.
+    pub fn new(settings: GameSettings, seed: u64) -> Self {
+        let start_level = settings.start_level;
+        let mut game = Game {
+            randomizer: settings.randomizer.build(),
+            settings,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            board: Board::default(),
+            next: VecDeque::new(),
+            active: None,
+            tile: 0,
+            hold: None,
+            hold_available: true,
+            score: ScoreState {
+                start_level,
+                ..Default::default()
+            },
+            section_level: SectionLevel::default(),
+            tick: 0,
+            spawn_at: None,
+            fall_progress: 0.0,
+            fall_interval: 0.0,
+            grounded: false,
+            spun: false,
+            lock: LockState::new(0),
+            auto_shift: AutoShift::default(),
+            top_out: None,
+            finished: false,
+            splits: Vec::new(),
+            zen_gravity: start_level,
+            entered: None,
+            history: Vec::new(),
+        };
+        game.fill(game.settings.queue_len());
+        let (shape, tile) = game.deal();
+        game.place(shape, tile, &mut Vec::new());
+        game
+    }
+
+    pub fn settings(&self) -> &GameSettings {
+        &self.settings
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn active(&self) -> Option<Piece> {
+        self.active
+    }
+
+    /// Tile index the active piece is drawn with.
+    pub fn tile(&self) -> usize {
+        self.tile
+    }
+
+    pub fn next(&self) -> impl Iterator<Item = &(Shape, usize)> + '_ {
+        self.next.iter()
+    }
+
+    pub fn hold(&self) -> Option<(Shape, usize)> {
+        self.hold
+    }
+
+    pub fn hold_available(&self) -> bool {
+        self.hold_available
+    }
+
+    pub fn score(&self) -> &ScoreState {
+        &self.score
+    }
+
+    pub fn section_level(&self) -> SectionLevel {
+        self.section_level
+    }
+
+    /// Ticks played so far.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Share of the lock delay the active piece has left.
+    pub fn lock_remaining(&self) -> f32 {
+        self.lock.remaining
+    }
+
+    pub fn top_out(&self) -> Option<TopOut> {
+        self.top_out
+    }
+
+    /// Whether the mode's goal was reached.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.finished || self.top_out.is_some()
+    }
+
+    pub fn splits(&self) -> &[u64] {
+        &self.splits
+    }
+
+    pub fn zen_gravity(&self) -> u32 {
+        self.zen_gravity
+    }
+
+    /// Steps the Zen gravity level, from none at `0` to 20G.
+    pub fn adjust_zen_gravity(&mut self, step: i32) {
+        self.zen_gravity = (self.zen_gravity as i32 + step).clamp(0, TWENTY_G_LEVEL as i32) as u32;
+    }
+
+    /// Locks that can be taken back.
+    pub fn undo_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Plays one tick: the next piece's entry, hold, then rotation and
+    /// shifts, then hard drop and locking, then gravity and soft drop. Does
+    /// nothing once the game is over.
+    pub fn step(&mut self, inputs: &Inputs) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.is_over() {
+            return events;
+        }
+
+        self.tick += 1;
+        if inputs.undo && self.settings.mode == Mode::Zen {
+            self.undo(&mut events);
+            return events;
+        }
+        if self.spawn_at.is_some_and(|at| self.tick >= at) {
+            self.spawn_at = None;
+            let (shape, tile) = self.deal();
+            self.place(shape, tile, &mut events);
+        }
+        if inputs.hold {
+            self.swap_hold(&mut events);
+        }
+        self.control(inputs);
+        if !self.settle(inputs.hard_drop, &mut events) {
+            self.apply_gravity(inputs.soft_drop);
+        }
+        if !self.is_over() && self.goal_reached() {
+            self.finish(&mut events);
+        }
+        events
+    }
+
+    /// Deals pieces until `len` are waiting.
+    fn fill(&mut self, len: usize) {
+        while self.next.len() < len {
+            let shape = self.randomizer.next_shape(&mut self.rng);
+            let tile = self.rng.gen_range(0..TILE_COLOURS) * 5;
+            self.next.push_back((shape, tile));
+        }
+    }
+
+    /// Takes the front piece and tops the queue back up.
+    fn deal(&mut self) -> (Shape, usize) {
+        self.fill(1);
+        let next = self.next.pop_front().unwrap();
+        self.fill(self.settings.queue_len());
+        next
+    }
+
+    /// Puts a fresh `shape` at the top of the board, ending the game if it
+    /// does not fit there. It then drops a row into view if it can, or at
+    /// 20G straight onto the stack.
+    fn place(&mut self, shape: Shape, tile: usize, events: &mut Vec<GameEvent>) {
+        let mut piece = Piece::spawn(shape);
+        if !self.board.fits(&piece) && self.end(TopOut::BlockOut, events) {
+            return;
+        }
+        if self.gravity() <= TWENTY_G {
+            piece = self.board.landing(&piece);
+        } else if self.board.fits(&piece.shifted(0, -1)) {
+            piece = piece.shifted(0, -1);
+        }
+        if self.settings.mode == Mode::Master {
+            self.section_level.piece_entered();
+        }
+
+        self.active = Some(piece);
+        self.tile = tile;
+        self.grounded = false;
+        self.spun = false;
+        self.lock = LockState::new(self.tick);
+        self.fall_progress = 0.0;
+        events.push(GameEvent::Spawned(shape));
+        if self.settings.mode == Mode::Zen {
+            self.entered = Some(Box::new(self.snapshot()));
+        }
+    }
+
+    /// Swaps the active piece with the held one, or with the next piece when
+    /// nothing is held yet.
+    fn swap_hold(&mut self, events: &mut Vec<GameEvent>) {
+        let current = match self.active {
+            Some(piece) if self.hold_available => (piece.shape, self.tile),
+            _ => return,
+        };
+
+        let (shape, tile) = match self.hold {
+            Some(held) => held,
+            None => self.deal(),
+        };
+        self.hold = Some(current);
+        self.hold_available = false;
+        events.push(GameEvent::Held);
+        self.place(shape, tile, events);
+    }
+
+    /// Hard drops and runs the lock delay, returning whether the piece locked.
+    fn settle(&mut self, hard_drop: bool, events: &mut Vec<GameEvent>) -> bool {
+        let mut piece = match self.active {
+            Some(piece) => piece,
+            None => return false,
+        };
+
+        if hard_drop {
+            let distance = self.board.drop_distance(&piece);
+            piece = piece.shifted(0, -distance);
+            self.score.award_drop(distance as u32, true);
+            self.spun &= distance == 0;
+        }
+
+        let now = self.tick;
+        self.grounded = self.board.drop_distance(&piece) == 0;
+        let new_lowest = piece.origin.1 < self.lock.lowest_row;
+        if new_lowest {
+            self.lock.lowest_row = piece.origin.1;
+            self.lock.resets = 0;
+        }
+        let restart = match self.settings.lock_reset {
+            LockReset::Move => !self.grounded,
+            LockReset::Step => new_lowest,
+            LockReset::NoReset => !self.lock.touched,
+        };
+        if restart {
+            self.lock.started = now;
+        }
+        self.lock.touched |= self.grounded;
+
+        let delay = self.lock_delay();
+        let elapsed = now - self.lock.started;
+        self.lock.remaining = (1.0 - elapsed as f32 / delay.max(1) as f32).clamp(0.0, 1.0);
+        let spun_out = self.settings.lock_reset == LockReset::Move && self.lock.resets >= MAX_LOCK_RESETS;
+
+        if hard_drop || (self.grounded && (elapsed >= delay || spun_out)) {
+            self.lock_piece(piece, events);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Locks `piece`, clears and scores full rows and brings in the next piece.
+    fn lock_piece(&mut self, piece: Piece, events: &mut Vec<GameEvent>) {
+        if let Some(entered) = self.entered.take() {
+            self.history.push(*entered);
+        }
+        self.board.lock(&piece, self.tile);
+        self.active = None;
+        events.push(GameEvent::Locked(piece));
+        let locked_out = piece.cells().iter().all(|&(_, y)| y >= VISIBLE_HEIGHT);
+        if locked_out && self.end(TopOut::LockOut, events) {
+            return;
+        }
+
+        let t_spin = self.spun && t_spin(&self.board, &piece);
+        let rows = self.board.clear_full_rows();
+        let splits_before = self.score.lines / SPLIT_LINES;
+        self.score.award_lines(rows.len(), t_spin);
+        for _ in splits_before..self.score.lines / SPLIT_LINES {
+            self.splits.push(self.tick);
+        }
+        if self.settings.mode == Mode::Master {
+            self.section_level.lines_cleared(rows.len() as u32);
+        }
+        let cleared = !rows.is_empty();
+        if cleared {
+            events.push(GameEvent::LinesCleared(rows));
+        }
+        if self.goal_reached() {
+            self.finish(events);
+            return;
+        }
+        self.hold_available = true;
+        let delay = self.entry_delay(cleared);
+        if delay == 0 {
+            let (shape, tile) = self.deal();
+            self.place(shape, tile, events);
+        } else {
+            self.spawn_at = Some(self.tick + delay);
+        }
+    }
+
+    /// Ticks a piece falls one row in.
+    fn gravity(&self) -> f64 {
+        match self.settings.mode {
+            Mode::Master => TWENTY_G,
+            Mode::Zen => level_gravity(self.zen_gravity),
+            _ => self.score.gravity(),
+        }
+    }
+
+    /// Copies the game without its own snapshots.
+    fn snapshot(&mut self) -> Game {
+        let entered = self.entered.take();
+        let history = std::mem::take(&mut self.history);
+        let snapshot = self.clone();
+        self.entered = entered;
+        self.history = history;
+        snapshot
+    }
+
+    /// Goes back to when the last locked piece entered. The clock and
+    /// gravity carry on from now.
+    fn undo(&mut self, events: &mut Vec<GameEvent>) {
+        let snapshot = match self.history.pop() {
+            Some(snapshot) => snapshot,
+            None => return,
+        };
+        let history = std::mem::take(&mut self.history);
+        let (tick, zen_gravity) = (self.tick, self.zen_gravity);
+        *self = snapshot;
+        self.history = history;
+        self.tick = tick;
+        self.zen_gravity = zen_gravity;
+        self.fall_progress = 0.0;
+        self.grounded = false;
+        self.spun = false;
+        self.lock = LockState::new(tick);
+        self.auto_shift = AutoShift::default();
+        self.entered = Some(Box::new(self.snapshot()));
+        events.push(GameEvent::Undone);
+    }
+
+    fn lock_delay(&self) -> u64 {
+        match self.settings.mode {
+            Mode::Master => self.section_level.timing().lock_delay,
+            _ => ticks(self.settings.lock_delay),
+        }
+    }
+
+    /// Ticks between a lock, clearing lines if `cleared`, and the next piece.
+    fn entry_delay(&self, cleared: bool) -> u64 {
+        match self.settings.mode {
+            Mode::Master => {
+                let timing = self.section_level.timing();
+                timing.are + if cleared { timing.line_clear } else { 0 }
+            }
+            _ => ticks(self.settings.are),
+        }
+    }
+
+    /// Rotation and auto shift of the active piece, ahead of any hard drop
+    /// in the same tick.
+    fn control(&mut self, inputs: &Inputs) {
+        let mut piece = match self.active {
+            Some(piece) => piece,
+            None => return,
+        };
+
+        let now = self.tick;
+        let handling = self.settings.handling;
+        let grounded = self.board.drop_distance(&piece) == 0;
+        let mut moved = false;
+        if let Some(rotation) = inputs.rotation {
+            if self.board.try_rotate(&mut piece, rotation) {
+                self.auto_shift.cut_until = now + ticks(handling.dcd);
+                self.spun = true;
+                moved = true;
+            }
+        }
+        let rotated = piece;
+
+        let auto_shift = &mut self.auto_shift;
+        match inputs.horizontal {
+            Some(direction) => {
+                let dx = if direction == Direction::Left { -1 } else { 1 };
+                if auto_shift.direction != Some(direction) {
+                    moved |= self.board.try_shift(&mut piece, dx, 0);
+
+                    auto_shift.direction = Some(direction);
+                    auto_shift.repeat_at = now + ticks(handling.das);
+                } else if now >= auto_shift.repeat_at && now >= auto_shift.cut_until {
+                    if handling.arr == 0 {
+                        while self.board.try_shift(&mut piece, dx, 0) {
+                            moved = true;
+                        }
+                    } else {
+                        moved |= self.board.try_shift(&mut piece, dx, 0);
+
+                        auto_shift.repeat_at = now + ticks(handling.arr);
+                    }
+                }
+            }
+            None => auto_shift.direction = None,
+        }
+
+        self.spun &= piece.origin == rotated.origin;
+        if moved && self.lock.try_move_reset(self.settings.lock_reset, grounded) {
+            self.lock.started = now;
+        }
+        self.active = Some(piece);
+    }
+
+    /// Gravity and soft drop of a piece that is still in play after settling.
+    fn apply_gravity(&mut self, soft_drop: bool) {
+        let mut piece = match self.active {
+            Some(piece) => piece,
+            None => return,
+        };
+
+        // A grounded piece builds up no fall, so one sliding off a ledge
+        // waits a full interval before it drops.
+        if self.grounded {
+            self.fall_progress = 0.0;
+            return;
+        }
+        if soft_drop && self.settings.soft_drop == SoftDrop::Sonic {
+            let distance = self.board.drop_distance(&piece);
+            piece = piece.shifted(0, -distance);
+            self.score.award_drop(distance as u32, false);
+
+            self.fall_progress = 0.0;
+        } else {
+            let gravity = self.gravity();
+            let interval = if soft_drop {
+                // Soft drop still works with Zen gravity turned off.
+                self.settings.soft_drop.interval(gravity.min(level_gravity(1)))
+            } else {
+                gravity
+            };
+            // Progress built up at a slower interval must not count several
+            // times over at a faster one.
+            if interval != self.fall_interval {
+                self.fall_progress = self.fall_progress.min(interval);
+                self.fall_interval = interval;
+            }
+            let rows = fall(&self.board, &mut piece, &mut self.fall_progress, interval);
+            if soft_drop {
+                self.score.award_drop(rows, false);
+            }
+        }
+        self.spun &= piece == self.active.unwrap();
+        self.active = Some(piece);
+    }
+
+    fn goal_reached(&self) -> bool {
+        match self.settings.mode {
+            Mode::Marathon { endless } => !endless && self.score.lines >= MARATHON_LINES,
+            Mode::Sprint { lines } => self.score.lines >= lines,
+            Mode::Ultra => self.settings.mode.time_limit().is_some_and(|limit| self.tick >= limit),
+            Mode::Master => self.section_level.0 >= MASTER_MAX_LEVEL,
+            Mode::Zen => false,
+        }
+    }
+
+    fn finish(&mut self, events: &mut Vec<GameEvent>) {
+        self.finished = true;
+        events.push(GameEvent::Finished);
+    }
+
+    /// Tops out, returning whether that ended the game. Zen wipes the board
+    /// and plays on instead.
+    fn end(&mut self, top_out: TopOut, events: &mut Vec<GameEvent>) -> bool {
+        if self.settings.mode == Mode::Zen {
+            self.board = Board::default();
+            events.push(GameEvent::BoardWiped(top_out));
+            return false;
+        }
+        self.top_out = Some(top_out);
+        events.push(GameEvent::GameOver(top_out));
+        true
+    }
+}
+
+/// Whether a T locked as `piece` is wedged in by at least three of the four
+/// corners around its centre, the walls and floor counting as filled.
+fn t_spin(board: &Board, piece: &Piece) -> bool {
+    let (x, y) = piece.origin;
+    let corners = [(x, y), (x + 2, y), (x, y + 2), (x + 2, y + 2)];
+    piece.shape == Shape::TBlock && corners.iter().filter(|&&(x, y)| !board.is_free(x, y)).count() >= 3
+}
+
+/// Builds up one more tick of `progress` and drops `piece` a row for every
+/// whole `interval` built up, stopping on the stack. Returns the rows it
+/// fell; several rows in one tick are still checked one at a time.
+fn fall(board: &Board, piece: &mut Piece, progress: &mut f64, interval: f64) -> u32 {
+    let interval = interval.max(f64::EPSILON);
+    *progress += 1.0;
+    let rows = (*progress / interval).floor();
+    if rows < 1.0 {
+        return 0;
+    }
+    *progress -= rows * interval;
+    (0..rows as u32).take_while(|_| board.try_shift(piece, 0, -1)).count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::stack;
+
+    fn game() -> Game {
+        Game::new(GameSettings::default(), 1)
+    }
+
+    /// Puts `piece` in play on a board holding just the `filled` cells.
+    fn setup(game: &mut Game, filled: &[(i32, i32)], piece: Piece) {
+        game.board = Board::with_cells(filled);
+        game.active = Some(piece);
+        game.lock = LockState::new(game.tick);
+    }
+
+    /// A vertical I in the rightmost column, above four rows with a gap there.
+    fn ready_tetris(game: &mut Game) {
+        setup(game, &stack(4, 9), Piece { shape: Shape::IBlock, rotation: 1, origin: (7, 10) });
+    }
+
+    fn hard_drop() -> Inputs {
+        Inputs { hard_drop: true, ..Default::default() }
+    }
+
+    fn held(direction: Direction) -> Inputs {
+        Inputs { horizontal: Some(direction), ..Default::default() }
+    }
+
+    #[test]
+    fn hard_drop_locks_and_deals_the_next_piece() {
+        let mut game = game();
+        let next = *game.next().next().unwrap();
+        let events = game.step(&hard_drop());
+
+        assert!(matches!(events[0], GameEvent::Locked(_)));
+        assert_eq!(events[1], GameEvent::Spawned(next.0));
+        assert_eq!(game.board().occupied().count(), 4);
+        assert_eq!(game.score().score, 38);
+    }
+
+    #[test]
+    fn spawns_a_row_into_view_when_it_can() {
+        let game = game();
+        let piece = game.active().unwrap();
+        assert_eq!(piece, Piece::spawn(piece.shape).shifted(0, -1));
+        assert!(piece.cells().iter().any(|&(_, y)| y < VISIBLE_HEIGHT));
+
+        // Blocked just below, it stays in the hidden rows rather than topping out.
+        let mut game = game.clone();
+        game.board = Board::with_cells(&[(4, VISIBLE_HEIGHT - 1), (5, VISIBLE_HEIGHT - 1)]);
+        let events = game.step(&Inputs { hold: true, ..Default::default() });
+        let held = game.active().unwrap();
+        assert_eq!(events.last(), Some(&GameEvent::Spawned(held.shape)));
+        assert_eq!(held, Piece::spawn(held.shape));
+    }
+
+    #[test]
+    fn held_direction_stops_at_each_wall() {
+        let cases = [
+            (Shape::TBlock, Direction::Left, 0),
+            (Shape::TBlock, Direction::Right, 7),
+            (Shape::IBlock, Direction::Left, 0),
+            (Shape::IBlock, Direction::Right, 6),
+            (Shape::OBlock, Direction::Left, 0),
+            (Shape::OBlock, Direction::Right, 8),
+        ];
+        for (shape, direction, origin_x) in cases {
+            let mut game = game();
+            setup(&mut game, &[], Piece::spawn(shape));
+            for _ in 0..30 {
+                game.step(&held(direction));
+            }
+            assert_eq!(game.active().unwrap().origin.0, origin_x, "{:?} {:?}", shape, direction);
+        }
+    }
+
+    #[test]
+    fn auto_shift_waits_for_das_then_repeats_at_arr() {
+        let mut game = game();
+        setup(&mut game, &[], Piece::spawn(Shape::TBlock).shifted(-3, -10));
+        let das = ticks(game.settings.handling.das) as usize;
+        let arr = ticks(game.settings.handling.arr) as usize;
+        let positions: Vec<i32> = (0..das + 2 * arr + 1)
+            .map(|_| {
+                game.step(&held(Direction::Right));
+                game.active().unwrap().origin.0
+            })
+            .collect();
+
+        assert_eq!(positions[0], 1);
+        assert_eq!(positions[das - 1], 1);
+        assert_eq!(positions[das], 2);
+        assert_eq!(positions[das + arr - 1], 2);
+        assert_eq!(positions[das + arr], 3);
+    }
+
+    #[test]
+    fn soft_drop_started_mid_interval_falls_a_row_at_a_time() {
+        let mut game = game();
+        let spawned = game.active().unwrap();
+        for _ in 0..40 {
+            game.step(&Inputs::default());
+        }
+        assert_eq!(game.active(), Some(spawned));
+
+        let soft_drop = Inputs { soft_drop: true, ..Default::default() };
+        game.step(&soft_drop);
+        assert_eq!(game.active().unwrap().origin.1, spawned.origin.1 - 1);
+        assert_eq!(game.score().score, 1);
+        // 20X soft drop at level 1 falls a row every three ticks.
+        for _ in 0..3 {
+            game.step(&soft_drop);
+        }
+        assert_eq!(game.active().unwrap().origin.1, spawned.origin.1 - 2);
+    }
+
+    #[test]
+    fn sliding_off_a_ledge_waits_a_full_interval() {
+        let settings = GameSettings { start_level: 10, ..Default::default() };
+        let mut game = Game::new(settings, 1);
+        setup(&mut game, &[(0, 4)], Piece { shape: Shape::OBlock, rotation: 0, origin: (0, 5) });
+        // Rests on the ledge for most of the lock delay.
+        for _ in 0..25 {
+            game.step(&Inputs::default());
+        }
+        let resting = game.active().unwrap();
+        game.step(&held(Direction::Right));
+        assert_eq!(game.active().unwrap(), resting.shifted(1, 0));
+
+        // The tick it slid off counts as the first of the interval.
+        let interval = game.score().gravity().ceil() as usize;
+        for _ in 2..interval {
+            game.step(&Inputs::default());
+        }
+        assert_eq!(game.active().unwrap(), resting.shifted(1, 0));
+        game.step(&Inputs::default());
+        assert_eq!(game.active().unwrap(), resting.shifted(1, -1));
+    }
+
+    #[test]
+    fn moves_in_the_hard_drop_tick_apply_first() {
+        let board = Board::default();
+        let spawned = Piece::spawn(game().active().unwrap().shape);
+        let mut rotated = spawned;
+        assert!(board.try_rotate(&mut rotated, Rotation::Clockwise));
+        let cases = [
+            (Inputs { horizontal: Some(Direction::Left), ..hard_drop() }, spawned.shifted(-1, 0)),
+            (Inputs { rotation: Some(Rotation::Clockwise), ..hard_drop() }, rotated),
+        ];
+        for (inputs, moved) in cases {
+            let mut game = game();
+            let events = game.step(&inputs);
+            assert_eq!(events[0], GameEvent::Locked(board.landing(&moved)), "{:?}", inputs);
+        }
+    }
+
+    #[test]
+    fn grounded_piece_locks_after_the_lock_delay() {
+        let mut game = game();
+        setup(&mut game, &[], Piece::spawn(Shape::OBlock).shifted(0, -20));
+        // The lock lands on the piece's `delay`th tick on the ground.
+        let delay = ticks(game.settings.lock_delay);
+        for _ in 1..delay {
+            assert!(game.step(&Inputs::default()).is_empty());
+        }
+        assert!(matches!(game.step(&Inputs::default())[0], GameEvent::Locked(_)));
+    }
+
+    #[test]
+    fn lands_on_the_stack_before_locking() {
+        let mut game = game();
+        setup(&mut game, &stack(5, 0), Piece::spawn(Shape::TBlock));
+        let events = game.step(&hard_drop());
+
+        match events[0] {
+            GameEvent::Locked(piece) => assert_eq!(piece.cells().iter().map(|cell| cell.1).min(), Some(5)),
+            ref event => panic!("expected a lock, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn clears_and_scores_one_to_four_lines() {
+        let cases = [(1, 100), (2, 300), (3, 500), (4, 800)];
+        for (count, points) in cases {
+            let mut game = game();
+            // A vertical I in the rightmost column, above a stack with a gap there.
+            setup(&mut game, &stack(count, 9), Piece { shape: Shape::IBlock, rotation: 1, origin: (7, 10) });
+            let drop = game.board.drop_distance(&game.active().unwrap()) as u32;
+            let events = game.step(&hard_drop());
+
+            assert!(events.contains(&GameEvent::LinesCleared((0..count).collect())), "{} lines", count);
+            assert_eq!(game.score().lines, count as u32);
+            assert_eq!(game.score().score, points + drop * 2, "{} lines", count);
+            assert_eq!(game.board().occupied().count(), 4 - count as usize, "{} lines", count);
+        }
+    }
+
+    #[test]
+    fn tops_out() {
+        let spawn_area: Vec<(i32, i32)> = (3..7).flat_map(|x| (20..22).map(move |y| (x, y))).collect();
+        let cases = [
+            // The stack reaches the hidden rows, so the piece locks above the playfield.
+            (stack(20, 0), Piece::spawn(Shape::OBlock), TopOut::LockOut),
+            // The next piece has nowhere to spawn.
+            (spawn_area, Piece::spawn(Shape::OBlock).shifted(-4, -20), TopOut::BlockOut),
+        ];
+        for (filled, piece, top_out) in cases {
+            let mut game = game();
+            setup(&mut game, &filled, piece);
+            let events = game.step(&hard_drop());
+
+            assert_eq!(events.last(), Some(&GameEvent::GameOver(top_out)));
+            assert_eq!(game.top_out(), Some(top_out));
+            assert!(game.step(&hard_drop()).is_empty());
+        }
+    }
+
+    #[test]
+    fn sprint_finishes_at_the_line_goal() {
+        let settings = GameSettings { mode: Mode::Sprint { lines: 20 }, ..Default::default() };
+        let mut game = Game::new(settings, 1);
+        for tetris in 1..=5 {
+            ready_tetris(&mut game);
+            game.step(&Inputs::default());
+            let events = game.step(&hard_drop());
+
+            assert_eq!(game.splits().len(), tetris * 4 / 10);
+            assert_eq!(events.last() == Some(&GameEvent::Finished), tetris == 5);
+        }
+        assert_eq!(game.splits(), &[6, 10]);
+        assert!(game.finished());
+        assert!(game.step(&hard_drop()).is_empty());
+    }
+
+    #[test]
+    fn scores_a_t_spin_double() {
+        let mut game = game();
+        let mut filled = stack(2, 4);
+        filled.retain(|&cell| cell != (3, 1) && cell != (5, 1));
+        filled.push((3, 2));
+        // Standing upright in the slot, then turned so its point goes into the gap.
+        setup(&mut game, &filled, Piece { shape: Shape::TBlock, rotation: 1, origin: (3, 0) });
+        game.step(&Inputs { rotation: Some(Rotation::Clockwise), ..Default::default() });
+        let events = game.step(&hard_drop());
+
+        assert!(events.contains(&GameEvent::LinesCleared(vec![0, 1])));
+        assert_eq!(game.score().t_spins, 1);
+        assert_eq!(game.score().score, 1200);
+    }
+
+    #[test]
+    fn marathon_finishes_at_the_line_goal_unless_endless() {
+        for endless in [false, true] {
+            let settings = GameSettings { mode: Mode::Marathon { endless }, ..Default::default() };
+            let mut game = Game::new(settings, 1);
+            game.score.lines = MARATHON_LINES - 4;
+            ready_tetris(&mut game);
+            game.step(&hard_drop());
+
+            assert_eq!(game.finished(), !endless, "endless {}", endless);
+        }
+    }
+
+    #[test]
+    fn starts_on_the_chosen_level() {
+        let settings = GameSettings { start_level: 10, ..Default::default() };
+        let mut game = Game::new(settings, 1);
+        assert_eq!(game.score().level(), 10);
+        ready_tetris(&mut game);
+        let drop = game.board.drop_distance(&game.active().unwrap()) as u32;
+        game.step(&hard_drop());
+        assert_eq!(game.score().score, 800 * 10 + drop * 2);
+    }
+
+    #[test]
+    fn master_pieces_enter_on_the_stack_after_the_entry_delay() {
+        let settings = GameSettings { mode: Mode::Master, ..Default::default() };
+        let mut game = Game::new(settings, 1);
+        let piece = game.active().unwrap();
+        assert_eq!(game.board().drop_distance(&piece), 0);
+        assert_eq!(game.section_level().0, 1);
+
+        let are = game.section_level().timing().are;
+        assert_eq!(game.step(&hard_drop()).len(), 1);
+        for _ in 1..are {
+            assert!(game.step(&Inputs::default()).is_empty());
+            assert_eq!(game.active(), None);
+        }
+        assert!(matches!(game.step(&Inputs::default())[..], [GameEvent::Spawned(_)]));
+        assert_eq!(game.board().drop_distance(&game.active().unwrap()), 0);
+        assert_eq!(game.section_level().0, 2);
+    }
+
+    fn zen() -> Game {
+        Game::new(GameSettings { mode: Mode::Zen, ..Default::default() }, 1)
+    }
+
+    #[test]
+    fn zen_wipes_the_board_instead_of_topping_out() {
+        let mut game = zen();
+        setup(&mut game, &stack(20, 0), Piece::spawn(Shape::OBlock));
+        let events = game.step(&hard_drop());
+
+        assert!(events.contains(&GameEvent::BoardWiped(TopOut::LockOut)));
+        assert!(matches!(events.last(), Some(GameEvent::Spawned(_))));
+        assert_eq!(game.board().occupied().count(), 0);
+        assert!(!game.is_over());
+    }
+
+    #[test]
+    fn zen_undo_takes_back_each_lock() {
+        let mut game = zen();
+        let mut before = Vec::new();
+        for _ in 0..3 {
+            before.push((game.board().clone(), game.active().unwrap().shape, game.score().clone()));
+            game.step(&hard_drop());
+        }
+        assert_eq!(game.undo_len(), 3);
+
+        let undo = Inputs { undo: true, ..Default::default() };
+        while let Some((board, shape, score)) = before.pop() {
+            assert_eq!(game.step(&undo), vec![GameEvent::Undone]);
+            assert_eq!(game.board(), &board);
+            assert_eq!(game.active(), Some(Piece::spawn(shape).shifted(0, -1)));
+            assert_eq!(game.score(), &score);
+        }
+        assert!(game.step(&undo).is_empty());
+    }
+
+    #[test]
+    fn undo_is_ignored_outside_zen() {
+        let mut game = game();
+        let events = game.step(&Inputs { undo: true, ..hard_drop() });
+
+        assert!(matches!(events[0], GameEvent::Locked(_)));
+        assert_eq!(game.board().occupied().count(), 4);
+    }
+
+    #[test]
+    fn zen_gravity_can_be_turned_off() {
+        let mut game = zen();
+        game.adjust_zen_gravity(-(TWENTY_G_LEVEL as i32));
+        assert_eq!(game.zen_gravity(), 0);
+        let spawned = game.active();
+        for _ in 0..600 {
+            game.step(&Inputs::default());
+        }
+        assert_eq!(game.active(), spawned);
+
+        game.adjust_zen_gravity(100);
+        game.step(&Inputs::default());
+        assert_eq!(game.board().drop_distance(&game.active().unwrap()), 0);
+    }
+
+    #[test]
+    fn ultra_finishes_when_time_is_up() {
+        let settings = GameSettings { mode: Mode::Ultra, ..Default::default() };
+        let limit = settings.mode.time_limit().unwrap();
+        let mut game = Game::new(settings, 1);
+        game.tick = limit - 2;
+        assert!(!game.step(&Inputs::default()).contains(&GameEvent::Finished));
+        assert_eq!(game.step(&Inputs::default()).last(), Some(&GameEvent::Finished));
+        assert!(game.finished());
+    }
+
+    #[test]
+    fn hold_swaps_once_per_piece() {
+        let mut game = game();
+        let first = game.active().unwrap().shape;
+        let next = game.next().next().unwrap().0;
+        let hold = Inputs { hold: true, ..Default::default() };
+
+        assert_eq!(game.step(&hold), vec![GameEvent::Held, GameEvent::Spawned(next)]);
+        assert_eq!(game.hold().map(|held| held.0), Some(first));
+        assert!(!game.hold_available());
+        assert!(game.step(&hold).is_empty());
+
+        game.step(&hard_drop());
+        assert!(game.hold_available());
+        assert_eq!(game.step(&hold)[1], GameEvent::Spawned(first));
+    }
+
+    #[test]
+    fn same_seed_and_inputs_replay_the_same_game() {
+        let play = || {
+            let mut game = game();
+            for tick in 0..600 {
+                let direction = if tick % 50 < 25 { Direction::Left } else { Direction::Right };
+                game.step(&Inputs { hard_drop: tick % 20 == 0, ..held(direction) });
+            }
+            (game.board().clone(), game.score().clone())
+        };
+        assert_eq!(play(), play());
+    }
+}
\ No newline at end of file
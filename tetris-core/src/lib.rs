@@ -0,0 +1,16 @@
+//! Tetris rules without any engine attached: the board, pieces, rotation,
+//! randomizers and scoring, driven one fixed tick at a time through
+//! [`Game::step`].
+
+pub mod board;
+pub mod directions;
+pub mod game;
+pub mod master;
+pub mod piece;
+pub mod randomizer;
+pub mod rotation;
+pub mod score;
+pub mod settings;
+pub mod shapes;
+
+pub use game::{Game, GameEvent, Inputs, TopOut};
\ No newline at end of file
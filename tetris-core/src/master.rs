@@ -0,0 +1,85 @@
+//! The section level counter and delays of the Master mode.
+
+/// The game is won on reaching this level.
+pub const MASTER_MAX_LEVEL: u32 = 999;
+pub const SECTION_LEVELS: u32 = 100;
+
+/// Delays of a section, in ticks.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Timing {
+    /// Entry delay between a lock and the next piece.
+    pub are: u64,
+    /// Extra entry delay after a lock that cleared lines.
+    pub line_clear: u64,
+    pub lock_delay: u64,
+}
+
+/// Delays of sections `0..`, the last one holding to the end.
+const TIMINGS: [Timing; 6] = [
+    Timing { are: 18, line_clear: 12, lock_delay: 30 },
+    Timing { are: 14, line_clear: 6, lock_delay: 26 },
+    Timing { are: 14, line_clear: 6, lock_delay: 22 },
+    Timing { are: 8, line_clear: 6, lock_delay: 18 },
+    Timing { are: 7, line_clear: 5, lock_delay: 15 },
+    Timing { are: 6, line_clear: 4, lock_delay: 15 },
+];
+
+/// Level counter that rises by one for each piece that enters and by the
+/// number of lines each clear removes. A piece can't take it past the last
+/// level of a section; only clearing lines can.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct SectionLevel(pub u32);
+
+impl SectionLevel {
+    pub fn section(&self) -> u32 {
+        self.0 / SECTION_LEVELS
+    }
+
+    pub fn timing(&self) -> Timing {
+        TIMINGS[(self.section() as usize).min(TIMINGS.len() - 1)]
+    }
+
+    /// Counts a piece entering the board.
+    pub fn piece_entered(&mut self) {
+        if self.0 % SECTION_LEVELS != SECTION_LEVELS - 1 && self.0 < MASTER_MAX_LEVEL - 1 {
+            self.0 += 1;
+        }
+    }
+
+    pub fn lines_cleared(&mut self, count: u32) {
+        self.0 = (self.0 + count).min(MASTER_MAX_LEVEL);
+    }
+
+    /// Level that ends the current section.
+    pub fn section_goal(&self) -> u32 {
+        ((self.section() + 1) * SECTION_LEVELS).min(MASTER_MAX_LEVEL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pieces_stop_at_the_end_of_a_section() {
+        let cases = [(0, 1), (98, 99), (99, 99), (100, 101), (998, 998)];
+        for (level, entered) in cases {
+            let mut counter = SectionLevel(level);
+            counter.piece_entered();
+            assert_eq!(counter.0, entered, "from {}", level);
+        }
+    }
+
+    #[test]
+    fn lines_carry_into_the_next_section() {
+        let mut counter = SectionLevel(99);
+        counter.lines_cleared(2);
+        assert_eq!(counter.0, 101);
+        assert_eq!(counter.timing(), TIMINGS[1]);
+
+        counter.0 = 997;
+        counter.lines_cleared(4);
+        assert_eq!(counter.0, MASTER_MAX_LEVEL);
+        assert_eq!(counter.timing(), TIMINGS[5]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,59 @@
+use crate::{directions::Rotation, shapes::Shape};
+
+use super::board::VISIBLE_HEIGHT;
+
+/// A tetromino placed on the board grid.
+///
+/// `rotation` counts clockwise quarter turns from the spawn orientation and
+/// `origin` is the board cell of the bottom-left corner of the rotation box.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Piece {
+    pub shape: Shape,
+    pub rotation: usize,
+    pub origin: (i32, i32),
+}
+
+impl Piece {
+    /// Places `shape` in the hidden rows just above the visible playfield,
+    /// horizontally centered.
+    pub fn spawn(shape: Shape) -> Self {
+        let origin = match shape {
+            Shape::IBlock => (3, VISIBLE_HEIGHT - 2),
+            Shape::OBlock => (4, VISIBLE_HEIGHT),
+            _ => (3, VISIBLE_HEIGHT - 1),
+        };
+
+        Piece {
+            shape,
+            rotation: 0,
+            origin,
+        }
+    }
+
+    /// Board cells covered by the piece.
+    pub fn cells(&self) -> [(i32, i32); 4] {
+        let size = self.shape.box_size();
+        let mut cells = self.shape.minos();
+        for cell in cells.iter_mut() {
+            for _ in 0..self.rotation % 4 {
+                *cell = (cell.1, size - 1 - cell.0);
+            }
+            *cell = (cell.0 + self.origin.0, cell.1 + self.origin.1);
+        }
+        cells
+    }
+
+    pub fn rotated(&self, rotation: Rotation) -> Self {
+        Piece {
+            rotation: (self.rotation + rotation.quarter_turns()) % 4,
+            ..*self
+        }
+    }
+
+    pub fn shifted(&self, dx: i32, dy: i32) -> Self {
+        Piece {
+            origin: (self.origin.0 + dx, self.origin.1 + dy),
+            ..*self
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,121 @@
+use rand::{seq::SliceRandom, Rng, RngCore};
+
+use super::shapes::Shape;
+
+/// Picks the order pieces are dealt in.
+pub trait Randomizer: Send + Sync {
+    fn next_shape(&mut self, rng: &mut dyn RngCore) -> Shape;
+
+    /// Copies the randomizer mid-sequence, so a game can be snapshot.
+    fn box_clone(&self) -> Box<dyn Randomizer>;
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+/// Every piece drawn independently and uniformly.
+#[derive(Clone)]
+pub struct PureRandom;
+
+impl Randomizer for PureRandom {
+    fn next_shape(&mut self, rng: &mut dyn RngCore) -> Shape {
+        rng.gen()
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// Deals shuffled bags holding `copies` of each of the seven shapes.
+#[derive(Clone)]
+pub struct Bag {
+    copies: usize,
+    bag: Vec<Shape>,
+}
+
+impl Bag {
+    pub fn new(copies: usize) -> Self {
+        Bag {
+            copies,
+            bag: Vec::new(),
+        }
+    }
+}
+
+impl Randomizer for Bag {
+    fn next_shape(&mut self, rng: &mut dyn RngCore) -> Shape {
+        if self.bag.is_empty() {
+            for _ in 0..self.copies {
+                self.bag.extend_from_slice(&Shape::ALL);
+            }
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop().unwrap()
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// The NES algorithm: roll one of eight outcomes and reroll once if it
+/// repeats the previous piece or lands on the spare eighth outcome.
+#[derive(Clone, Default)]
+pub struct Nes {
+    last: Option<Shape>,
+}
+
+impl Randomizer for Nes {
+    fn next_shape(&mut self, rng: &mut dyn RngCore) -> Shape {
+        let roll = rng.gen_range(0..8);
+        let shape = match Shape::ALL.get(roll) {
+            Some(&shape) if Some(shape) != self.last => shape,
+            _ => Shape::ALL[rng.gen_range(0..7)],
+        };
+        self.last = Some(shape);
+        shape
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum RandomizerKind {
+    SevenBag,
+    FourteenBag,
+    Nes,
+    PureRandom,
+}
+
+impl RandomizerKind {
+    pub const ALL: [RandomizerKind; 4] = [
+        RandomizerKind::SevenBag,
+        RandomizerKind::FourteenBag,
+        RandomizerKind::Nes,
+        RandomizerKind::PureRandom,
+    ];
+
+    pub fn build(&self) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::SevenBag => Box::new(Bag::new(1)),
+            RandomizerKind::FourteenBag => Box::new(Bag::new(2)),
+            RandomizerKind::Nes => Box::new(Nes::default()),
+            RandomizerKind::PureRandom => Box::new(PureRandom),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RandomizerKind::SevenBag => "7-BAG",
+            RandomizerKind::FourteenBag => "14-BAG",
+            RandomizerKind::Nes => "NES",
+            RandomizerKind::PureRandom => "RANDOM",
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,52 @@
+//! Super Rotation System wall kick data.
+//!
+//! Offsets are `(dx, dy)` with y pointing up and are tried in order until the
+//! rotated piece fits. Rotation states are 0 = spawn, 1 = R, 2 = 180, 3 = L.
+
+use crate::{directions::Rotation, shapes::Shape};
+
+const JLSTZ_KICKS: [[(i32, i32); 5]; 8] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // 0 -> R
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],     // R -> 0
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],     // R -> 2
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // 2 -> R
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],    // 2 -> L
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],  // L -> 2
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],  // L -> 0
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],    // 0 -> L
+];
+
+const I_KICKS: [[(i32, i32); 5]; 8] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],  // 0 -> R
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],  // R -> 0
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],  // R -> 2
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],  // 2 -> R
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],  // 2 -> L
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],  // L -> 2
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],  // L -> 0
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],  // 0 -> L
+];
+
+/// 180° kicks, which SRS itself does not define, indexed by starting state.
+const HALF_KICKS: [[(i32, i32); 6]; 4] = [
+    [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],    // 0 -> 2
+    [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],      // R -> L
+    [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)], // 2 -> 0
+    [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],   // L -> R
+];
+
+/// Offsets to try when rotating `shape` out of rotation state `from`.
+pub fn kicks(shape: Shape, from: usize, rotation: Rotation) -> &'static [(i32, i32)] {
+    let from = from % 4;
+    let row = match rotation {
+        Rotation::Clockwise => [0, 2, 4, 6][from],
+        Rotation::CounterClockwise => [7, 1, 3, 5][from],
+        Rotation::Half => return &HALF_KICKS[from],
+    };
+
+    match shape {
+        Shape::OBlock => &[(0, 0)],
+        Shape::IBlock => &I_KICKS[row],
+        _ => &JLSTZ_KICKS[row],
+    }
+}
\ No newline at end of file
@@ -0,0 +1,129 @@
+use crate::settings::TICKS_PER_SECOND;
+
+const LINES_PER_LEVEL: u32 = 10;
+/// From this level on pieces fall 20 rows a tick, straight to the stack.
+pub const TWENTY_G_LEVEL: u32 = 20;
+/// Ticks per row of 20G gravity.
+pub const TWENTY_G: f64 = 1.0 / 20.0;
+
+/// Score, level and line count of a game, following the guideline scoring
+/// table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreState {
+    pub score: u32,
+    pub lines: u32,
+    pub start_level: u32,
+    /// Consecutive locks that cleared lines, `None` once the chain breaks.
+    pub combo: Option<u32>,
+    /// Whether the last line clear was a tetris or a T-spin.
+    pub back_to_back: bool,
+    pub tetrises: u32,
+    /// T-spins, with or without lines.
+    pub t_spins: u32,
+    /// Longest chain of consecutive line clears after the first.
+    pub max_combo: u32,
+}
+
+impl Default for ScoreState {
+    fn default() -> Self {
+        ScoreState {
+            score: 0,
+            lines: 0,
+            start_level: 1,
+            combo: None,
+            back_to_back: false,
+            tetrises: 0,
+            t_spins: 0,
+            max_combo: 0,
+        }
+    }
+}
+
+/// Ticks it takes a piece to fall one row at `level`, following
+/// `(0.8 - (level - 1) * 0.007)^(level - 1)` seconds up to 20G. Level `0`
+/// has no gravity at all.
+pub fn level_gravity(level: u32) -> f64 {
+    if level == 0 {
+        return f64::INFINITY;
+    }
+    if level >= TWENTY_G_LEVEL {
+        return TWENTY_G;
+    }
+    let level = (level - 1) as f64;
+    ((0.8 - level * 0.007).powf(level) * TICKS_PER_SECOND as f64).max(TWENTY_G)
+}
+
+impl ScoreState {
+    pub fn level(&self) -> u32 {
+        self.start_level + self.lines / LINES_PER_LEVEL
+    }
+
+    /// Ticks it takes a piece to fall one row at the current level.
+    pub fn gravity(&self) -> f64 {
+        level_gravity(self.level())
+    }
+
+    /// Scores a lock that cleared `count` lines, `0` included, and was a
+    /// T-spin if `t_spin`.
+    pub fn award_lines(&mut self, count: usize, t_spin: bool) {
+        let level = self.level();
+        if t_spin {
+            self.t_spins += 1;
+        }
+        if count == 0 {
+            if t_spin {
+                self.score += 400 * level;
+            }
+            self.combo = None;
+            return;
+        }
+
+        let base = if t_spin {
+            400 * (count as u32 + 1)
+        } else {
+            match count {
+                1 => 100,
+                2 => 300,
+                3 => 500,
+                _ => 800,
+            }
+        } * level;
+        let difficult = count >= 4 || t_spin;
+        self.score += if difficult && self.back_to_back {
+            base * 3 / 2
+        } else {
+            base
+        };
+        self.back_to_back = difficult;
+
+        let combo = self.combo.map_or(0, |combo| combo + 1);
+        self.score += 50 * combo * level;
+        self.combo = Some(combo);
+        self.max_combo = self.max_combo.max(combo);
+        self.lines += count as u32;
+        if count >= 4 {
+            self.tetrises += 1;
+        }
+    }
+
+    /// Scores `cells` rows of soft (1 point each) or hard (2 points each) drop.
+    pub fn award_drop(&mut self, cells: u32, hard: bool) {
+        self.score += if hard { cells * 2 } else { cells };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gravity_speeds_up_to_twenty_g() {
+        let gravity = |level: u32| ScoreState { start_level: level, ..Default::default() }.gravity();
+        assert_eq!(level_gravity(0), f64::INFINITY);
+        assert_eq!(gravity(1), 60.0);
+        assert!((gravity(2) - 47.6).abs() < 0.1);
+        assert!((gravity(15) - 0.42).abs() < 0.01);
+        assert_eq!(gravity(TWENTY_G_LEVEL), TWENTY_G);
+        assert_eq!(gravity(200), TWENTY_G);
+    }
+}
\ No newline at end of file
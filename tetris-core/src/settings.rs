@@ -0,0 +1,215 @@
+use super::randomizer::RandomizerKind;
+
+/// Options picked in the menu that apply to the next game.
+#[derive(Debug, Clone)]
+pub struct GameSettings {
+    pub randomizer: RandomizerKind,
+    /// Seed for the game's random number generator, a fresh one each game
+    /// when `None`.
+    pub seed: Option<u64>,
+    /// Upcoming pieces shown beside the playfield, `0..=MAX_PREVIEW`.
+    pub preview: usize,
+    pub ghost: bool,
+    pub soft_drop: SoftDrop,
+    pub handling: Handling,
+    /// Milliseconds a grounded piece waits before locking.
+    pub lock_delay: u32,
+    /// Entry delay: milliseconds between a lock and the next piece.
+    pub are: u32,
+    pub lock_reset: LockReset,
+    pub mode: Mode,
+    /// Level the game starts on, `1..=MAX_START_LEVEL`.
+    pub start_level: u32,
+}
+
+pub const MAX_PREVIEW: usize = 6;
+
+/// What a game is played for, and so what ends it besides topping out.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Mode {
+    /// Clear `MARATHON_LINES` lines as the levels speed up, or keep playing
+    /// until the stack tops out if `endless`.
+    Marathon { endless: bool },
+    /// Clear `lines` lines as fast as possible.
+    Sprint { lines: u32 },
+    /// Score as much as possible in `ULTRA_SECONDS`.
+    Ultra,
+    /// Climb the section levels to `MASTER_MAX_LEVEL` at 20G, the delays
+    /// shrinking each section.
+    Master,
+    /// Play without an end: topping out wipes the board, locks can be taken
+    /// back and the gravity is picked freely.
+    Zen,
+}
+
+pub const ULTRA_SECONDS: u32 = 120;
+pub const MARATHON_LINES: u32 = 150;
+pub const MAX_START_LEVEL: u32 = 15;
+
+impl Mode {
+    pub const ALL: [Mode; 8] = [
+        Mode::Marathon { endless: true },
+        Mode::Marathon { endless: false },
+        Mode::Sprint { lines: 20 },
+        Mode::Sprint { lines: 40 },
+        Mode::Sprint { lines: 100 },
+        Mode::Ultra,
+        Mode::Master,
+        Mode::Zen,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Mode::Marathon { endless: true } => String::from("ENDLESS"),
+            Mode::Marathon { endless: false } => format!("MARATHON {}", MARATHON_LINES),
+            Mode::Sprint { lines } => format!("SPRINT {}", lines),
+            Mode::Ultra => String::from("ULTRA"),
+            Mode::Master => String::from("MASTER"),
+            Mode::Zen => String::from("ZEN"),
+        }
+    }
+
+    /// Ticks the game lasts, if it is played against the clock.
+    pub fn time_limit(&self) -> Option<u64> {
+        match self {
+            Mode::Ultra => Some(ticks(ULTRA_SECONDS * 1000)),
+            _ => None,
+        }
+    }
+}
+
+/// Horizontal auto-repeat timings, in milliseconds.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Handling {
+    /// Delayed auto shift: how long a direction is held before it repeats.
+    pub das: u32,
+    /// Auto repeat rate: time between repeated shifts, `0` for instant.
+    pub arr: u32,
+    /// DAS cut: pause in auto-repeat after a rotation.
+    pub dcd: u32,
+}
+
+impl Handling {
+    pub const PROFILES: [(&'static str, Handling); 3] = [
+        ("GUIDELINE", Handling { das: 167, arr: 33, dcd: 0 }),
+        ("CLASSIC", Handling { das: 267, arr: 100, dcd: 0 }),
+        ("FAST", Handling { das: 117, arr: 0, dcd: 17 }),
+    ];
+    pub const MAX_DELAY: u32 = 1000;
+
+    pub fn profile_name(&self) -> &'static str {
+        Handling::PROFILES
+            .iter()
+            .find(|(_, handling)| handling == self)
+            .map_or("CUSTOM", |(name, _)| name)
+    }
+}
+
+/// What restarts the lock delay of a grounded piece.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LockReset {
+    /// Any successful move or rotation, up to `MAX_LOCK_RESETS` times per row.
+    Move,
+    /// Only falling to a new lowest row.
+    Step,
+    /// Nothing: the delay runs from the first touch down.
+    NoReset,
+}
+
+impl LockReset {
+    pub const ALL: [LockReset; 3] = [LockReset::Move, LockReset::Step, LockReset::NoReset];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LockReset::Move => "MOVE",
+            LockReset::Step => "STEP",
+            LockReset::NoReset => "NONE",
+        }
+    }
+}
+
+pub const MAX_LOCK_RESETS: u32 = 15;
+
+/// Rate the gameplay rules are simulated at.
+pub const TICKS_PER_SECOND: u32 = 60;
+
+/// Converts a millisecond setting to whole ticks, rounded to the nearest.
+pub fn ticks(ms: u32) -> u64 {
+    ((ms * TICKS_PER_SECOND + 500) / 1000) as u64
+}
+
+/// Converts a tick count back to milliseconds, rounded down.
+pub fn millis(ticks: u64) -> u64 {
+    ticks * 1000 / TICKS_PER_SECOND as u64
+}
+
+/// How fast a held soft drop falls.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SoftDrop {
+    /// Multiple of the current gravity.
+    Factor(u32),
+    /// Straight to the landing row, without locking.
+    Sonic,
+}
+
+impl SoftDrop {
+    pub const ALL: [SoftDrop; 5] = [
+        SoftDrop::Factor(5),
+        SoftDrop::Factor(10),
+        SoftDrop::Factor(20),
+        SoftDrop::Factor(40),
+        SoftDrop::Sonic,
+    ];
+
+    /// Ticks per row while soft dropping at `gravity` ticks per row.
+    pub fn interval(&self, gravity: f64) -> f64 {
+        match self {
+            SoftDrop::Factor(factor) => gravity / *factor as f64,
+            SoftDrop::Sonic => 0.0,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            SoftDrop::Factor(factor) => format!("{}X", factor),
+            SoftDrop::Sonic => String::from("SONIC"),
+        }
+    }
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        GameSettings {
+            randomizer: RandomizerKind::SevenBag,
+            seed: None,
+            preview: 5,
+            ghost: true,
+            soft_drop: SoftDrop::Factor(20),
+            handling: Handling::PROFILES[0].1,
+            lock_delay: 500,
+            are: 0,
+            lock_reset: LockReset::Move,
+            mode: Mode::Marathon { endless: true },
+            start_level: 1,
+        }
+    }
+}
+
+impl GameSettings {
+    /// Pieces to keep dealt ahead: the previewed ones, and at least the next.
+    pub fn queue_len(&self) -> usize {
+        self.preview.max(1)
+    }
+
+    /// Reads `--seed <n>` from the command line arguments.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Self {
+        let mut settings = GameSettings::default();
+        let mut args = args.skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--seed" {
+                settings.seed = args.next().and_then(|seed| seed.parse().ok());
+            }
+        }
+        settings
+    }
+}
\ No newline at end of file
@@ -0,0 +1,59 @@
+use rand::{
+    Rng,
+    distributions::{
+        Distribution,
+        Standard
+    }
+};
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Shape {
+    IBlock,
+    LBlock,
+    JBlock,
+    SBlock,
+    ZBlock,
+    TBlock,
+    OBlock,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 7] = [
+        Shape::IBlock,
+        Shape::LBlock,
+        Shape::JBlock,
+        Shape::SBlock,
+        Shape::ZBlock,
+        Shape::TBlock,
+        Shape::OBlock,
+    ];
+
+    /// Side length of the square box the shape rotates in.
+    pub fn box_size(&self) -> i32 {
+        match self {
+            Shape::IBlock => 4,
+            Shape::OBlock => 2,
+            _ => 3,
+        }
+    }
+
+    /// Cells of the spawn orientation, relative to the bottom-left corner of
+    /// the rotation box, with y pointing up.
+    pub fn minos(&self) -> [(i32, i32); 4] {
+        match self {
+            Shape::IBlock => [(0, 2), (1, 2), (2, 2), (3, 2)],
+            Shape::LBlock => [(2, 2), (0, 1), (1, 1), (2, 1)],
+            Shape::JBlock => [(0, 2), (0, 1), (1, 1), (2, 1)],
+            Shape::SBlock => [(1, 2), (2, 2), (0, 1), (1, 1)],
+            Shape::ZBlock => [(0, 2), (1, 2), (1, 1), (2, 1)],
+            Shape::TBlock => [(1, 2), (0, 1), (1, 1), (2, 1)],
+            Shape::OBlock => [(0, 0), (1, 0), (0, 1), (1, 1)],
+        }
+    }
+}
+
+impl Distribution<Shape> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Shape {
+        Shape::ALL[rng.gen_range(0..Shape::ALL.len())]
+    }
+}
\ No newline at end of file